@@ -1,90 +1,125 @@
 use proc_macro2::TokenStream;
 use quote::{quote, ToTokens};
-use syn::{Attribute, DataStruct, Field, Ident};
+use syn::{Attribute, DataStruct, Field, Ident, Lifetime, Path, Type};
 use synstructure::Structure;
 
-use crate::{extract_attrs_optional_tag, FieldAttrs, Tag};
+use crate::{decoder_lifetime, extract_attrs_optional_tag, option_inner, FieldAttrs, Tag};
 
 /// Derive Decodable on a struct
 pub(crate) struct DeriveDecodableStruct {
+    /// Path to the `flexiber` crate
+    krate: Path,
+
     /// Field decoders
     decode_fields: TokenStream,
 
     /// Bound fields of a struct to be returned
     decode_result: TokenStream,
+
+    /// Whether TLVs with tags of no field are skipped
+    ignore_unknown: bool,
+
+    /// Tags of the fields
+    known_tags: Vec<Tag>,
+
+    /// Lifetime of the decoded bytes
+    lifetime: Lifetime,
+
+    /// Generics declared on the impl in addition to the ones of the struct
+    impl_generics: TokenStream,
+
+    /// Compile errors for malformed attributes
+    errors: TokenStream,
 }
 
 impl DeriveDecodableStruct {
-    pub fn derive(s: Structure<'_>, data: &DataStruct, name: &Ident, attrs: &[Attribute]) -> TokenStream {
+    pub fn derive(
+        s: Structure<'_>,
+        data: &DataStruct,
+        name: &Ident,
+        attrs: &[Attribute],
+    ) -> TokenStream {
+        let attrs = extract_attrs_optional_tag(name, attrs);
+        let tag = attrs.container_tag();
 
-        let (tag, _slice) = extract_attrs_optional_tag(name, attrs);
+        let (lifetime, impl_generics) = match decoder_lifetime(&s, name) {
+            Ok(lifetime) => lifetime,
+            Err(error) => return error,
+        };
 
         let mut state = Self {
+            krate: attrs.crate_path(),
             decode_fields: TokenStream::new(),
             decode_result: TokenStream::new(),
+            ignore_unknown: attrs.ignore_unknown,
+            known_tags: Vec::new(),
+            lifetime,
+            impl_generics,
+            errors: attrs.container_tag_check(),
         };
+        state.errors.extend(attrs.errors);
 
         for field in &data.fields {
             state.derive_field(field);
         }
 
+        if state.ignore_unknown {
+            if tag.is_none() {
+                return syn::Error::new_spanned(
+                    name,
+                    "`ignore_unknown` requires a tag on the struct, delimiting the TLVs to skip",
+                )
+                .to_compile_error();
+            }
+            let simple = matches!(state.known_tags.first(), Some(Tag::Simple(_)));
+            if state
+                .known_tags
+                .iter()
+                .any(|tag| matches!(tag, Tag::Simple(_)) != simple)
+            {
+                return syn::Error::new_spanned(
+                    name,
+                    "`ignore_unknown` requires all fields to use either BER or simple tags",
+                )
+                .to_compile_error();
+            }
+        }
+
         state.finish(&s, tag)
     }
 
     /// Derive handling for a particular `#[field(...)]`
     fn derive_field(&mut self, field: &Field) {
         let attrs = FieldAttrs::new(field);
+        self.errors.extend(attrs.errors.clone());
         self.derive_field_decoder(&attrs);
     }
 
     /// Derive code for decoding a field of a message
     fn derive_field_decoder(&mut self, field: &FieldAttrs) {
+        let krate = &self.krate;
         let field_name = &field.name;
-        let tag = field.tag;
-
-        let field_decoder = match tag {
-            Tag::Ber(tag) => {
-                let class = tag.class as u8;
-                let constructed = tag.constructed;
-                let tag_number = tag.number;
-
-                if field.slice {
-                    quote! {
-                        let tag = ::flexiber::Tag::from(
-                            flexiber::Class::try_from(#class).unwrap(),
-                            #constructed,
-                            #tag_number
-                        );
-                        let #field_name =
-                            decoder.decode_tagged_slice(tag)?.try_into().unwrap();
-                                // .map_err(|_| flexiber::ErrorKind::Length { tag })?;
-                    }
-                } else {
-                    quote! {
-                        let tag = ::flexiber::Tag::from(
-                            flexiber::Class::try_from(#class).unwrap(),
-                            #constructed,
-                            #tag_number
-                        );
-
-                        let #field_name = decoder.decode_tagged_value(tag)?;
-                    }
-                }
-            }
-            Tag::Simple(tag) => {
-                let field_tag = tag.0;
-                if field.slice {
-                    quote! { let #field_name =
-                        decoder.decode_tagged_slice(::flexiber::SimpleTag::try_from(#field_tag).unwrap())?.try_into()
-                            .map_err(|_| {
-                                use flexiber::TagLike;
-                                flexiber::ErrorKind::Length { tag: flexiber::SimpleTag::try_from(#field_tag).unwrap().embedding() }
-                            })?;
-                    }
-                } else {
-                    quote! { let #field_name = decoder.decode_tagged_value(::flexiber::SimpleTag::try_from(#field_tag).unwrap())?; }
-                }
-            }
+        let tag = field.tag.to_expr(krate);
+
+        if field.skip {
+            quote! { let #field_name = ::core::default::Default::default(); }
+                .to_tokens(&mut self.decode_fields);
+            quote!(#field_name,).to_tokens(&mut self.decode_result);
+            return;
+        }
+
+        self.known_tags.push(field.tag.clone());
+        if self.ignore_unknown {
+            quote! { skip_unknown(decoder)?; }.to_tokens(&mut self.decode_fields);
+        }
+
+        let field_decoder = if field.slice {
+            self.slice_decoder(field, tag)
+        } else if field.optional {
+            // optional fields are detected by their own tag, not the one of the inner type
+            quote! { let #field_name = decoder.decode_optional_tagged_value(#tag)?; }
+        } else {
+            quote! { let #field_name = decoder.decode_tagged_value(#tag)?; }
         };
         field_decoder.to_tokens(&mut self.decode_fields);
 
@@ -92,76 +127,154 @@ impl DeriveDecodableStruct {
         field_result.to_tokens(&mut self.decode_result);
     }
 
+    /// Derive code for decoding a `#[tlv(slice)]` field via `TryFrom<&[u8]>`
+    ///
+    /// If the conversion fails, the error reports the content length, along with the
+    /// expected length in case the field is an array. An optional field is converted
+    /// if its tag is next, like other optional fields.
+    fn slice_decoder(&self, field: &FieldAttrs, tag: TokenStream) -> TokenStream {
+        let krate = &self.krate;
+        let field_name = &field.name;
+        let ty = match option_inner(&field.ty) {
+            Some(inner) if field.optional => inner,
+            _ => &field.ty,
+        };
+        let expected = match ty {
+            Type::Array(array) => {
+                let len = &array.len;
+                quote! { ::core::option::Option::Some(#len) }
+            }
+            _ => quote! { ::core::option::Option::None },
+        };
+
+        let convert = quote! {
+            {
+                let expected: ::core::option::Option<usize> = #expected;
+                let actual: #krate::Length = ::core::convert::TryFrom::try_from(slice.len())?;
+                ::core::convert::TryInto::try_into(slice).map_err(|_| #krate::ErrorKind::Length {
+                    tag: #krate::TagLike::embedding(tag),
+                    expected: expected.and_then(|expected| ::core::convert::TryFrom::try_from(expected).ok()),
+                    actual,
+                })?
+            }
+        };
+
+        if field.optional {
+            quote! {
+                let #field_name = {
+                    let tag = #tag;
+                    match decoder.decode_optional_tagged_value::<_, &[u8]>(tag)? {
+                        ::core::option::Option::Some(slice) => ::core::option::Option::Some(#convert),
+                        ::core::option::Option::None => ::core::option::Option::None,
+                    }
+                };
+            }
+        } else {
+            quote! {
+                let #field_name = {
+                    let tag = #tag;
+                    let slice = decoder.decode_tagged_slice(tag)?;
+                    #convert
+                };
+            }
+        }
+    }
+
+    /// Derive the `skip_unknown` closure used with `#[tlv(ignore_unknown)]`,
+    /// along with the final call skipping unknown TLVs after the last field
+    fn skip_unknown(&self) -> (TokenStream, TokenStream) {
+        if !self.ignore_unknown {
+            return (TokenStream::new(), TokenStream::new());
+        }
+
+        let krate = &self.krate;
+        let lifetime = &self.lifetime;
+        let tag_type = match self.known_tags.first() {
+            Some(Tag::Simple(_)) => quote!(#krate::SimpleTag),
+            _ => quote!(#krate::Tag),
+        };
+        let count = self.known_tags.len();
+        let known_tags = self.known_tags.iter().map(|tag| tag.to_expr(krate));
+
+        let skip_unknown = quote! {
+            let known_tags: [#tag_type; #count] = [#(#known_tags),*];
+            let skip_unknown = |decoder: &mut #krate::Decoder<#lifetime>| -> #krate::Result<()> {
+                while let ::core::option::Option::Some(tag) = decoder.peek_tag::<#tag_type>() {
+                    if known_tags.contains(&tag) {
+                        break;
+                    }
+                    decoder.skip_tlv::<#tag_type>()?;
+                }
+                ::core::result::Result::Ok(())
+            };
+        };
+        (skip_unknown, quote! { skip_unknown(decoder)?; })
+    }
+
     /// Finish deriving a struct
+    ///
+    /// Generated code only uses absolute paths, so it also expands in modules
+    /// without the implicit prelude.
     fn finish(self, s: &Structure<'_>, tag: Option<Tag>) -> TokenStream {
-
+        let (skip_unknown, skip_trailing) = self.skip_unknown();
+        let krate = self.krate;
         let decode_fields = self.decode_fields;
         let decode_result = self.decode_result;
+        let lifetime = self.lifetime;
+        let impl_generics = self.impl_generics;
+        let errors = self.errors;
 
-        if let Some(tag) = tag {
-
-            match tag {
-                Tag::Ber(tag) => {
-                    let class = tag.class as u8;
-                    let constructed = tag.constructed;
-                    let tag_number = tag.number;
-
-                    s.gen_impl(quote! {
-                        gen impl<'a> core::convert::TryFrom<flexiber::TaggedSlice<'a>> for @Self {
-                            type Error = flexiber::Error;
-
-                            fn try_from(tagged_slice: flexiber::TaggedSlice<'a>) -> flexiber::Result<Self> {
-                                use core::convert::TryInto;
-                                use flexiber::TagLike;
-                                let tag = ::flexiber::Tag::from(
-                                    flexiber::Class::try_from(#class).unwrap(),
-                                    #constructed,
-                                    #tag_number
-                                );
-                                tagged_slice.tag().assert_eq(tag)?;
-                                tagged_slice.decode_nested(|decoder| {
-                                    #decode_fields
-
-                                    Ok(Self { #decode_result })
-                                })
-                            }
+        let mut tokens = match tag {
+            Some(tag @ (Tag::Ber(_) | Tag::Path(_))) => {
+                let tag = tag.to_expr(&krate);
+                s.gen_impl(quote! {
+                    gen impl #impl_generics ::core::convert::TryFrom<#krate::TaggedSlice<#lifetime>> for @Self {
+                        type Error = #krate::Error;
+
+                        fn try_from(tagged_slice: #krate::TaggedSlice<#lifetime>) -> #krate::Result<Self> {
+                            #krate::TagLike::assert_eq(tagged_slice.tag(), #tag)?;
+                            tagged_slice.decode_nested(|decoder| {
+                                #skip_unknown
+                                #decode_fields
+                                #skip_trailing
+
+                                ::core::result::Result::Ok(Self { #decode_result })
+                            })
                         }
-                    })
-                }
-                Tag::Simple(tag) => {
-                    let tag = tag.0;
-                    s.gen_impl(quote! {
-                        gen impl<'a> Decodable<'a> for @Self {
-                            fn decode(decoder: &mut Decoder<'a>) -> Result<Self> {
-                                flexiber::TaggedSlice::<'a, flexiber::SimpleTag>::decode(decoder)
-                                    .and_then(|tagged_slice| {
-                                        use core::convert::TryInto;
-                                        use flexiber::TagLike;
-                                        tagged_slice.tag().assert_eq(flexiber::SimpleTag::try_from(#tag).unwrap())?;
-                                        tagged_slice.decode_nested(|decoder| {
-                                            #decode_fields
-
-                                            Ok(Self { #decode_result })
-                                        })
-                                    })
-                                    .or_else(|e| decoder.error(e.kind()))
-                            }
+                    }
+                })
+            }
+            Some(tag @ Tag::Simple(_)) => {
+                let tag = tag.to_expr(&krate);
+                s.gen_impl(quote! {
+                    gen impl #impl_generics #krate::Decodable<#lifetime> for @Self {
+                        fn decode(decoder: &mut #krate::Decoder<#lifetime>) -> #krate::Result<Self> {
+                            decoder
+                                .decode_tagged(#tag)
+                                .and_then(|tagged_slice| {
+                                    tagged_slice.decode_nested(|decoder| {
+                                        #skip_unknown
+                                        #decode_fields
+                                        #skip_trailing
 
+                                        ::core::result::Result::Ok(Self { #decode_result })
+                                    })
+                                })
+                                .or_else(|e| decoder.error(e.kind()))
                         }
-                    })
-                }
+                    }
+                })
             }
-        } else {
-            s.gen_impl(quote! {
-                gen impl<'a> flexiber::Decodable<'a> for @Self {
-                    fn decode(decoder: &mut flexiber::Decoder<'a>) -> flexiber::Result<Self> {
-                        use core::convert::{TryFrom, TryInto};
+            None => s.gen_impl(quote! {
+                gen impl #impl_generics #krate::Decodable<#lifetime> for @Self {
+                    fn decode(decoder: &mut #krate::Decoder<#lifetime>) -> #krate::Result<Self> {
                         #decode_fields
-                        Ok(Self { #decode_result })
+                        ::core::result::Result::Ok(Self { #decode_result })
                     }
                 }
-            })
-        }
+            }),
+        };
+        tokens.extend(errors);
+        tokens
     }
 }
-
@@ -1,150 +1,169 @@
 use proc_macro2::TokenStream;
 use quote::{quote, ToTokens};
-use syn::{Attribute, DataStruct, Field, Ident};
+use syn::{Attribute, DataStruct, Field, Ident, Path};
 use synstructure::Structure;
 
 use crate::{extract_attrs_optional_tag, FieldAttrs, Tag};
 
 /// Derive Encodable on a struct
 pub(crate) struct DeriveEncodableStruct {
+    /// Path to the `flexiber` crate
+    krate: Path,
+
     /// Fields of a struct to be serialized
     encode_fields: TokenStream,
 }
 
 impl DeriveEncodableStruct {
-    pub fn derive(s: Structure<'_>, data: &DataStruct, name: &Ident, attrs: &[Attribute]) -> TokenStream {
-
-        let (tag, _slice) = extract_attrs_optional_tag(name, attrs);
+    pub fn derive(
+        s: Structure<'_>,
+        data: &DataStruct,
+        name: &Ident,
+        attrs: &[Attribute],
+    ) -> TokenStream {
+        let attrs = extract_attrs_optional_tag(name, attrs);
+        let tag = attrs.container_tag();
+        let allow_duplicate = attrs.allow_duplicate;
 
         let mut state = Self {
+            krate: attrs.crate_path(),
             encode_fields: TokenStream::new(),
         };
 
+        let mut errors = attrs.container_tag_check();
+        errors.extend(attrs.errors);
+        let mut field_tags: Vec<Tag> = Vec::new();
+
         for field in &data.fields {
-            state.derive_field(field);
+            let attrs = FieldAttrs::new(field);
+            errors.extend(attrs.errors.clone());
+            if attrs.skip {
+                continue;
+            }
+            if !(allow_duplicate || attrs.allow_duplicate) {
+                if field_tags.contains(&attrs.tag) {
+                    errors.extend(Self::duplicate_tag_error(field, &attrs));
+                } else {
+                    // tags set by a path are only known to the compiler
+                    let message = Self::duplicate_tag_message(&attrs);
+                    for previous in &field_tags {
+                        errors.extend(attrs.tag.const_distinct(previous, &state.krate, &message));
+                    }
+                }
+            }
+            field_tags.push(attrs.tag.clone());
+            state.derive_field_encoder(&attrs);
         }
 
-        state.finish(&s, tag)
+        let mut tokens = state.finish(&s, tag);
+        tokens.extend(errors);
+        tokens
     }
 
-    /// Derive handling for a particular `#[field(...)]`
-    fn derive_field(&mut self, field: &Field) {
-        let attrs = FieldAttrs::new(field);
-        self.derive_field_encoder(&attrs);
+    /// A generic reader cannot tell apart fields sharing a tag, so this is
+    /// rejected unless `#[tlv(allow_duplicate)]` is set on the field or the struct.
+    fn duplicate_tag_error(field: &Field, attrs: &FieldAttrs) -> TokenStream {
+        syn::Error::new_spanned(field, Self::duplicate_tag_message(attrs)).to_compile_error()
+    }
+
+    fn duplicate_tag_message(attrs: &FieldAttrs) -> String {
+        format!(
+            "field `{}` reuses the tag of a previous field, \
+             add `#[tlv(allow_duplicate)]` if this is intended",
+            attrs.name
+        )
     }
 
     /// Derive code for encoding a field of a message
     fn derive_field_encoder(&mut self, field: &FieldAttrs) {
+        let krate = &self.krate;
         let field_name = &field.name;
-        let tag = field.tag;
-
-        let field_encoder = match tag {
-            Tag::Ber(tag) => {
-                let class = tag.class as u8;
-                let constructed = tag.constructed;
-                let tag_number = tag.number;
+        let tag = field.tag.to_expr(krate);
 
-                if field.slice {
-                    quote! { &(::flexiber::TaggedSlice::from(flexiber::Tag::from(flexiber::Class::try_from(#class).unwrap(), #constructed, #tag_number), &self.#field_name)?), }
-                } else {
-                    quote! { &(::flexiber::Tag::from(flexiber::Class::try_from(#class).unwrap(), #constructed, #tag_number).with_value(&self.#field_name)), }
-                }
-            }
-            Tag::Simple(tag) => {
-                let field_tag = tag.0;
-                if field.slice {
-                    quote! { &(::flexiber::TaggedSlice::from(flexiber::SimpleTag::try_from(#field_tag).unwrap(), &self.#field_name)?), }
-                } else {
-                    quote! { &(::flexiber::SimpleTag::try_from(#field_tag).unwrap().with_value(&self.#field_name)), }
-                }
+        let field_encoder = if field.slice && field.optional {
+            quote! {
+                &(self.#field_name.as_ref().map(|value| #krate::TaggedSlice::from(#tag, value)).transpose()?),
             }
+        } else if field.slice {
+            quote! { &(#krate::TaggedSlice::from(#tag, &self.#field_name)?), }
+        } else if field.optional {
+            quote! { &(self.#field_name.as_ref().map(|value| #krate::TagLike::with_value(#tag, value))), }
+        } else {
+            quote! { &(#krate::TagLike::with_value(#tag, &self.#field_name)), }
         };
         field_encoder.to_tokens(&mut self.encode_fields);
     }
 
     /// Finish deriving a struct
+    ///
+    /// Generated code only uses absolute paths, so it also expands in modules
+    /// without the implicit prelude.
     fn finish(self, s: &Structure<'_>, tag: Option<Tag>) -> TokenStream {
-
-
+        let krate = self.krate;
         let encode_fields = self.encode_fields;
 
-        if let Some(tag) = tag {
-            match tag {
-                Tag::Ber(tag) => {
-                    let class = tag.class as u8;
-                    let constructed = tag.constructed;
-                    let tag_number = tag.number;
-                    s.gen_impl(quote! {
-                        gen impl flexiber::Tagged for @Self {
-                            fn tag() -> flexiber::Tag {
-                                // TODO(nickray): FIXME FIXME
-                                use core::convert::TryFrom;
-                                flexiber::Tag::from(flexiber::Class::try_from(#class).unwrap(), #constructed, #tag_number)
-                            }
-                        }
+        let container = quote! {
+            gen impl #krate::Container for @Self {
+                fn fields<F, T>(&self, field_encoder: F) -> #krate::Result<T>
+                where
+                    F: ::core::ops::FnOnce(&[&dyn #krate::Encodable]) -> #krate::Result<T>,
+                {
+                    field_encoder(&[#encode_fields])
+                }
+            }
+        };
 
-                        gen impl flexiber::Container for @Self {
-                            fn fields<F, T>(&self, field_encoder: F) -> flexiber::Result<T>
-                            where
-                                F: FnOnce(&[&dyn flexiber::Encodable]) -> flexiber::Result<T>,
-                            {
-                                use core::convert::TryFrom;
-                                use flexiber::TagLike;
-                                field_encoder(&[#encode_fields])
-                            }
+        match tag {
+            Some(tag @ (Tag::Ber(_) | Tag::Path(_))) => {
+                let tag = tag.to_expr(&krate);
+                s.gen_impl(quote! {
+                    gen impl #krate::Tagged for @Self {
+                        fn tag() -> #krate::Tag {
+                            #tag
                         }
-                    })
-                }
-                Tag::Simple(tag) => {
-                    let tag = tag.0;
-                    s.gen_impl(quote! {
-                        gen impl flexiber::Tagged for @Self {
-                            fn tag() -> flexiber::Tag {
-                                use core::convert::TryFrom;
-                                flexiber::Tag::try_from(#tag).unwrap()
-                            }
+                    }
+
+                    #container
+                })
+            }
+            // the `Tagged` blanket impl would frame the content with a BER-TLV length
+            Some(tag @ Tag::Simple(_)) => {
+                let tag = tag.to_expr(&krate);
+                s.gen_impl(quote! {
+                    #container
+
+                    gen impl #krate::Encodable for @Self {
+                        fn encoded_length(&self) -> #krate::Result<#krate::Length> {
+                            #krate::Container::fields(self, |encodables| {
+                                #krate::Length::simple_tlv_length(
+                                    <#krate::Length as ::core::convert::TryFrom<_>>::try_from(encodables)?,
+                                )
+                            })
                         }
 
-                        gen impl flexiber::Container for @Self {
-                            fn fields<F, T>(&self, field_encoder: F) -> flexiber::Result<T>
-                            where
-                                F: FnOnce(&[&dyn flexiber::Encodable]) -> flexiber::Result<T>,
-                            {
-                                use core::convert::TryFrom;
-                                use flexiber::TagLike;
-                                field_encoder(&[#encode_fields])
-                            }
+                        fn encode(&self, encoder: &mut #krate::Encoder<'_>) -> #krate::Result<()> {
+                            #krate::Container::fields(self, |fields| {
+                                encoder.encode_simple_tagged_collection(#tag, fields)
+                            })
                         }
-                    })
-                }
-            }
-        } else {
-            s.gen_impl(quote! {
-                gen impl flexiber::Container for @Self {
-                    fn fields<F, T>(&self, field_encoder: F) -> flexiber::Result<T>
-                    where
-                        F: FnOnce(&[&dyn flexiber::Encodable]) -> flexiber::Result<T>,
-                    {
-                        use core::convert::TryFrom;
-                        use flexiber::TagLike;
-                        field_encoder(&[#encode_fields])
                     }
-                }
-
-                gen impl flexiber::Encodable for @Self {
-                    fn encoded_length(&self) -> flexiber::Result<flexiber::Length> {
-                        use core::convert::TryFrom;
-                        use flexiber::Container;
-                        self.fields(|encodables| flexiber::Length::try_from(encodables))
+                })
+            }
+            None => s.gen_impl(quote! {
+                #container
+
+                gen impl #krate::Encodable for @Self {
+                    fn encoded_length(&self) -> #krate::Result<#krate::Length> {
+                        #krate::Container::fields(self, |encodables| {
+                            <#krate::Length as ::core::convert::TryFrom<_>>::try_from(encodables)
+                        })
                     }
 
-                    fn encode(&self, encoder: &mut flexiber::Encoder<'_>) -> flexiber::Result<()> {
-                        use flexiber::Container;
-                        self.fields(|fields| encoder.encode_untagged_collection(fields))
+                    fn encode(&self, encoder: &mut #krate::Encoder<'_>) -> #krate::Result<()> {
+                        #krate::Container::fields(self, |fields| encoder.encode_untagged_collection(fields))
                     }
                 }
-            })
+            }),
         }
     }
 }
-
@@ -0,0 +1,126 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{Attribute, DataEnum, Fields, Ident, Path};
+use synstructure::Structure;
+
+use crate::extract_attrs_optional_tag;
+
+/// Derive Decodable and Encodable on a fieldless enum, as ASN.1 ENUMERATED
+pub(crate) struct DeriveEnumerated {
+    /// Path to the `flexiber` crate
+    krate: Path,
+
+    /// Names of the variants, whose discriminants are the encoded values
+    variants: Vec<Ident>,
+}
+
+impl DeriveEnumerated {
+    /// Collect the variants, or return a compile error if the enum is not a fieldless `#[repr(u8)]` enum
+    fn new(data: &DataEnum, name: &Ident, attrs: &[Attribute]) -> Result<Self, TokenStream> {
+        let tlv_attrs = extract_attrs_optional_tag(name, attrs);
+        if !tlv_attrs.errors.is_empty() {
+            return Err(tlv_attrs.errors);
+        }
+        if tlv_attrs.tag.is_some() {
+            return Err(syn::Error::new_spanned(
+                name,
+                "enums are encoded as ENUMERATED, with the universal tag `0x0A`; \
+                 set a tag on the field using the enum instead",
+            )
+            .to_compile_error());
+        }
+
+        let is_repr_u8 = attrs.iter().any(|attr| {
+            attr.path().is_ident("repr")
+                && attr
+                    .parse_args::<Ident>()
+                    .map(|repr| repr == "u8")
+                    .unwrap_or(false)
+        });
+        if !is_repr_u8 {
+            return Err(syn::Error::new_spanned(
+                name,
+                "enums must be `#[repr(u8)]` to derive `Decodable` or `Encodable`",
+            )
+            .to_compile_error());
+        }
+
+        let mut variants = Vec::new();
+        for variant in &data.variants {
+            if !matches!(variant.fields, Fields::Unit) {
+                return Err(syn::Error::new_spanned(
+                    variant,
+                    "only fieldless variants can be encoded as ENUMERATED",
+                )
+                .to_compile_error());
+            }
+            variants.push(variant.ident.clone());
+        }
+
+        Ok(Self {
+            krate: tlv_attrs.crate_path(),
+            variants,
+        })
+    }
+
+    pub fn derive_decodable(
+        s: Structure<'_>,
+        data: &DataEnum,
+        name: &Ident,
+        attrs: &[Attribute],
+    ) -> TokenStream {
+        let state = match Self::new(data, name, attrs) {
+            Ok(state) => state,
+            Err(error) => return error,
+        };
+        let krate = state.krate;
+        let variants = state.variants;
+
+        s.gen_impl(quote! {
+            gen impl<'a> #krate::Decodable<'a> for @Self {
+                fn decode(decoder: &mut #krate::Decoder<'a>) -> #krate::Result<Self> {
+                    let value = <#krate::Enumerated as #krate::Decodable<'a>>::decode(decoder)?.0;
+                    #(
+                        if value == Self::#variants as u8 {
+                            return ::core::result::Result::Ok(Self::#variants);
+                        }
+                    )*
+                    decoder.error(#krate::ErrorKind::InvalidValue {
+                        tag: ::core::option::Option::Some(#krate::Tag::ENUMERATED),
+                    })
+                }
+            }
+        })
+    }
+
+    pub fn derive_encodable(
+        s: Structure<'_>,
+        data: &DataEnum,
+        name: &Ident,
+        attrs: &[Attribute],
+    ) -> TokenStream {
+        let state = match Self::new(data, name, attrs) {
+            Ok(state) => state,
+            Err(error) => return error,
+        };
+        let krate = state.krate;
+        let variants = state.variants;
+        let enumerated = quote! {
+            #krate::Enumerated(match *self {
+                #(Self::#variants => Self::#variants as u8,)*
+            })
+        };
+
+        s.gen_impl(quote! {
+            gen impl #krate::Encodable for @Self {
+                fn encoded_length(&self) -> #krate::Result<#krate::Length> {
+                    #krate::Encodable::encoded_length(&#enumerated)
+                }
+
+                fn encode(&self, encoder: &mut #krate::Encoder<'_>) -> #krate::Result<()> {
+                    #krate::Encodable::encode(&#enumerated, encoder)
+                }
+            }
+        })
+    }
+}
@@ -3,6 +3,67 @@
 //! With `#[tlv(slice)]` set, `Encodable` should work for fields implementing `AsRef<[u8]>`,
 //! and `Decodable` should work for fields implementing `TryFrom<[u8]>`, even if the field
 //! is not `Decodable` or `Encodable`.
+//!
+//! Without `#[tlv(slice)]`, a field is framed by its tag: `Encodable` writes the tag and length,
+//! followed by the field's own encoding, and `Decodable` checks the tag, then decodes the field
+//! from the content bytes only, which it must consume entirely. A hand-written `Decodable`, e.g.
+//! for a bitfield, therefore never sees the header of the field.
+//!
+//! The length of a data object follows its tag: SIMPLE-TLV lengths for `#[tlv(simple = "..")]`,
+//! on the struct as well as on its fields, BER-TLV lengths otherwise.
+//!
+//! A tag set on a struct is constructed by default, as it wraps the fields.
+//! Set `#[tlv(primitive)]` to clear the constructed bit. Structs without fields, such as
+//! `struct Empty;` or `struct Empty {}`, are encoded as their tag with empty content.
+//!
+//! Fields of type `Option<T>` are omitted when `None`, and decoded as `Some` if the next
+//! tag is the one of the field (not necessarily `T`'s own tag). This includes `#[tlv(slice)]`
+//! fields such as `Option<[u8; 4]>`. Set `#[tlv(optional)]` if the type is an `Option` that
+//! is not spelled as such, e.g. through an alias.
+//!
+//! Structs without a `#[tlv(...)]` tag are encoded as the sequence of their fields, each
+//! framed by its own tag. As usual, `Decodable::from_bytes` rejects trailing data after the
+//! last field, while `Decodable::decode` leaves it for the next value.
+//!
+//! Structs may be generic over a lifetime, for fields borrowing from the decoded bytes such as
+//! `&'a [u8]`, and over constants, e.g. for `[u8; N]` fields, in any combination. Borrowed
+//! fields, with or without `#[tlv(slice)]`, point into the decoded bytes without a copy, also
+//! in structs nested in other fields.
+//!
+//! Generated code refers to the `flexiber` crate as `::flexiber`. If it is renamed or
+//! re-exported, set `#[tlv(crate = "path::to::flexiber")]` on the struct.
+//!
+//! With `#[tlv(ignore_unknown)]` set on a tagged struct, `Decodable` skips TLVs whose tag is
+//! not the one of any field, e.g. elements added by newer card firmware. The known fields are
+//! still expected in the declared order. All fields must use either BER or simple tags.
+//!
+//! Fieldless `#[repr(u8)]` enums are encoded as ASN.1 ENUMERATED (universal tag `0x0A`) of
+//! their discriminant, see `flexiber::Enumerated`. Decoding an unknown discriminant fails with
+//! `ErrorKind::InvalidValue`.
+//!
+//! Tag numbers are hexadecimal, with or without a `0x` prefix: BER tag numbers range from `0x0`
+//! to `0x3fff`, the largest number fitting in two subsequent octets, simple tag numbers from
+//! `0x1` to `0xfe`.
+//!
+//! Instead of a class and number, a tag may refer to a `const` defined once, e.g.
+//! `#[tlv(tag = "crate::tags::DISCOVERY")]` for a `const DISCOVERY: flexiber::Tag`. The constant
+//! is used as is, so on a struct it must be constructed, which fails to compile otherwise;
+//! `primitive` and `constructed` do not apply to it.
+//!
+//! Several `#[tlv(...)]` attributes on the same item accumulate, so `#[tlv(application)]`
+//! followed by `#[tlv(number = "0x2f")]` is the same as `#[tlv(application, number = "0x2f")]`.
+//!
+//! With `#[tlv(transparent)]` set, a struct with a single field, named or not, is encoded
+//! exactly as that field, e.g. `struct KeyRef(u8)` as a single content byte. No tag may be set.
+//!
+//! Fields with `#[tlv(skip)]` set are neither encoded nor decoded, and need no tag. `Decodable`
+//! sets them to their `Default`, e.g. a `_marker: PhantomData<&'a ()>` tying the struct to the
+//! lifetime of the decoded bytes.
+//!
+//! Fields sharing the same tag are rejected by the `Encodable` derive, as a generic reader
+//! could not tell them apart. Set `#[tlv(allow_duplicate)]` on the field or the struct to
+//! opt out, e.g. for repeated entries that are decoded positionally. Tags set by a `tag` path
+//! are compared by value at compile time, so `tags::AID` and `crate::tags::AID` clash.
 
 #![crate_type = "proc-macro"]
 #![warn(rust_2018_idioms, trivial_casts, unused_qualifications)]
@@ -11,15 +72,24 @@ mod decodable;
 use decodable::DeriveDecodableStruct;
 mod encodable;
 use encodable::DeriveEncodableStruct;
+mod enumerated;
+use enumerated::DeriveEnumerated;
+mod transparent;
+use transparent::DeriveTransparent;
 
+use core::fmt;
 use proc_macro2::TokenStream;
-use syn::{Attribute, Field, Ident, LitStr, Token};
+use quote::{format_ident, quote, ToTokens};
+use syn::{
+    meta::ParseNestedMeta, Attribute, Field, GenericArgument, Ident, Lifetime, LitStr, Path,
+    PathArguments, Type,
+};
 use synstructure::{decl_derive, Structure};
 
 decl_derive!(
     [Decodable, attributes(tlv)] =>
 
-    /// Derive the [`Decodable`][1] trait on a struct.
+    /// Derive the [`Decodable`][1] trait on a struct or fieldless enum.
     ///
     /// See [toplevel documentation for the `flexiber_derive` crate][2] for more
     /// information about how to use this macro.
@@ -32,7 +102,7 @@ decl_derive!(
 decl_derive!(
     [Encodable, attributes(tlv)] =>
 
-    /// Derive the [`Encodable`][1] trait on a struct.
+    /// Derive the [`Encodable`][1] trait on a struct or fieldless enum.
     ///
     /// See [toplevel documentation for the `flexiber_derive` crate][2] for more
     /// information about how to use this macro.
@@ -46,9 +116,16 @@ decl_derive!(
 fn derive_decodable(s: Structure<'_>) -> TokenStream {
     let ast = s.ast();
 
-    // TODO: enum support
     match &ast.data {
+        syn::Data::Struct(data)
+            if extract_attrs_optional_tag(&ast.ident, &ast.attrs).transparent =>
+        {
+            DeriveTransparent::derive_decodable(&s, data, &ast.ident, &ast.attrs)
+        }
         syn::Data::Struct(data) => DeriveDecodableStruct::derive(s, data, &ast.ident, &ast.attrs),
+        syn::Data::Enum(data) => {
+            DeriveEnumerated::derive_decodable(s, data, &ast.ident, &ast.attrs)
+        }
         other => panic!("can't derive `Decodable` on: {:?}", other),
     }
 }
@@ -57,27 +134,55 @@ fn derive_decodable(s: Structure<'_>) -> TokenStream {
 fn derive_encodable(s: Structure<'_>) -> TokenStream {
     let ast = s.ast();
 
-    // TODO: enum support
     match &ast.data {
+        syn::Data::Struct(data)
+            if extract_attrs_optional_tag(&ast.ident, &ast.attrs).transparent =>
+        {
+            DeriveTransparent::derive_encodable(&s, data, &ast.ident, &ast.attrs)
+        }
         syn::Data::Struct(data) => DeriveEncodableStruct::derive(s, data, &ast.ident, &ast.attrs),
+        syn::Data::Enum(data) => {
+            DeriveEnumerated::derive_encodable(s, data, &ast.ident, &ast.attrs)
+        }
         other => panic!("can't derive `Encodable` on: {:?}", other),
     }
 }
 
-#[derive(Clone, Copy, Debug, Default)]
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
 struct BerTag {
     class: Class,
     constructed: bool,
     number: u16,
 }
 
-#[derive(Clone, Copy, Debug, Default)]
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
 struct SimpleTag(u8);
 
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Debug, Eq, PartialEq)]
 enum Tag {
     Ber(BerTag),
     Simple(SimpleTag),
+    /// Path to a `const` BER tag, set with `#[tlv(tag = "...")]`
+    Path(TagPath),
+}
+
+/// Path to a `const` BER tag, compared as written: `tags::A` and `crate::tags::A` differ,
+/// see [`Tag::const_distinct`] for the check of the values
+#[derive(Clone)]
+struct TagPath(Path);
+
+impl PartialEq for TagPath {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.to_token_stream().to_string() == other.0.to_token_stream().to_string()
+    }
+}
+
+impl Eq for TagPath {}
+
+impl fmt::Debug for TagPath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "TagPath({})", self.0.to_token_stream())
+    }
 }
 
 impl From<BerTag> for Tag {
@@ -98,7 +203,64 @@ impl Default for Tag {
     }
 }
 
-#[derive(Clone, Copy, Debug)]
+impl Tag {
+    /// Expression constructing this tag in generated code
+    ///
+    /// Only absolute paths are used, so the expression does not depend on
+    /// the prelude or imports at the expansion site.
+    fn to_expr(&self, krate: &Path) -> TokenStream {
+        match self {
+            Tag::Ber(tag) => {
+                let class = match tag.class {
+                    Class::Universal => format_ident!("Universal"),
+                    Class::Application => format_ident!("Application"),
+                    Class::Context => format_ident!("Context"),
+                    Class::Private => format_ident!("Private"),
+                };
+                let constructed = tag.constructed;
+                let number = tag.number;
+                quote! { #krate::Tag::from(#krate::Class::#class, #constructed, #number) }
+            }
+            Tag::Simple(tag) => {
+                let number = tag.0;
+                quote! {
+                    <#krate::SimpleTag as ::core::convert::TryFrom<u8>>::try_from(#number).unwrap()
+                }
+            }
+            Tag::Path(TagPath(path)) => quote! { #path },
+        }
+    }
+
+    /// Compile time check that this tag and a previous field's tag differ, for a path
+    /// `tag`, whose value is only known to the compiler
+    ///
+    /// Empty unless one of the tags is a path and neither is a simple tag, which
+    /// cannot be a `const` BER tag.
+    fn const_distinct(&self, previous: &Tag, krate: &Path, message: &str) -> TokenStream {
+        match (self, previous) {
+            (Tag::Simple(_), _) | (_, Tag::Simple(_)) | (Tag::Ber(_), Tag::Ber(_)) => {
+                TokenStream::new()
+            }
+            _ => {
+                let tag = self.to_expr(krate);
+                let previous = previous.to_expr(krate);
+                quote! {
+                    const _: () = {
+                        let (tag, previous): (#krate::Tag, #krate::Tag) = (#tag, #previous);
+                        ::core::assert!(
+                            !(tag.class as u8 == previous.class as u8
+                                && tag.constructed == previous.constructed
+                                && tag.number == previous.number),
+                            #message
+                        );
+                    };
+                }
+            }
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
 #[repr(u8)]
 enum Class {
     Universal = 0b00,
@@ -119,11 +281,27 @@ struct FieldAttrs {
     /// Name of the field
     pub name: Ident,
 
+    /// Type of the field
+    pub ty: Type,
+
     /// Value of tag to use
     pub tag: Tag,
 
     /// Whether the `#[tlv(slice)]` attribute was set
     pub slice: bool,
+
+    /// Whether the field is an `Option`, which is omitted if `None`,
+    /// or the `#[tlv(optional)]` attribute was set
+    pub optional: bool,
+
+    /// Whether the `#[tlv(allow_duplicate)]` attribute was set
+    pub allow_duplicate: bool,
+
+    /// Whether the `#[tlv(skip)]` attribute was set
+    pub skip: bool,
+
+    /// Compile errors for malformed attributes, to be emitted with the derived code
+    pub errors: TokenStream,
 }
 
 impl FieldAttrs {
@@ -135,26 +313,207 @@ impl FieldAttrs {
             .cloned()
             .expect("no name on struct field i.e. tuple structs unsupported");
 
-        let (tag, slice) = extract_attrs(&name, &field.attrs);
+        let (
+            tag,
+            TlvAttrs {
+                slice,
+                optional,
+                allow_duplicate,
+                skip,
+                errors,
+                ..
+            },
+        ) = extract_attrs(&name, &field.attrs);
+
+        Self {
+            name,
+            ty: field.ty.clone(),
+            tag,
+            slice,
+            optional: optional || is_option(&field.ty),
+            allow_duplicate,
+            skip,
+            errors,
+        }
+    }
+}
+
+/// Whether the type is syntactically an `Option<_>`
+fn is_option(ty: &Type) -> bool {
+    match ty {
+        Type::Path(type_path) if type_path.qself.is_none() => type_path
+            .path
+            .segments
+            .last()
+            .map(|segment| {
+                segment.ident == "Option"
+                    && matches!(segment.arguments, PathArguments::AngleBracketed(_))
+            })
+            .unwrap_or(false),
+        _ => false,
+    }
+}
+
+/// The `T` of a type that is syntactically an `Option<T>`
+fn option_inner(ty: &Type) -> Option<&Type> {
+    if !is_option(ty) {
+        return None;
+    }
+    match ty {
+        Type::Path(type_path) => match &type_path.path.segments.last()?.arguments {
+            PathArguments::AngleBracketed(arguments) => {
+                arguments.args.iter().find_map(|argument| match argument {
+                    GenericArgument::Type(ty) => Some(ty),
+                    _ => None,
+                })
+            }
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Parsed `#[tlv(...)]` attributes of a struct or field
+#[derive(Debug, Default)]
+struct TlvAttrs {
+    /// Value of tag to use, if a tag number was set
+    pub tag: Option<Tag>,
+
+    /// Whether the `#[tlv(slice)]` attribute was set
+    pub slice: bool,
+
+    /// Whether the `#[tlv(optional)]` attribute was set
+    pub optional: bool,
+
+    /// Whether the `#[tlv(allow_duplicate)]` attribute was set
+    pub allow_duplicate: bool,
+
+    /// Whether the `#[tlv(skip)]` attribute was set
+    pub skip: bool,
+
+    /// Whether the `#[tlv(ignore_unknown)]` attribute was set
+    pub ignore_unknown: bool,
+
+    /// Whether the `#[tlv(transparent)]` attribute was set
+    pub transparent: bool,
+
+    /// Whether the `#[tlv(primitive)]` attribute was set (and not overridden by `constructed`)
+    pub primitive: bool,
+
+    /// Path set with the `#[tlv(crate = "...")]` attribute
+    pub krate: Option<Path>,
+
+    /// Compile errors for malformed attributes, to be emitted with the derived code
+    pub errors: TokenStream,
+}
+
+impl TlvAttrs {
+    /// Path to the `flexiber` crate in generated code, `::flexiber` unless overridden
+    fn crate_path(&self) -> Path {
+        self.krate
+            .clone()
+            .unwrap_or_else(|| syn::parse_quote!(::flexiber))
+    }
+
+    /// Compile time check that a `tag` path on a struct is constructed, as the struct wraps
+    /// the fields; unlike for class and number, the derive cannot set the constructed bit
+    fn container_tag_check(&self) -> TokenStream {
+        match &self.tag {
+            Some(tag @ Tag::Path(TagPath(path))) => {
+                let krate = self.crate_path();
+                let tag = tag.to_expr(&krate);
+                let message = format!(
+                    "`tag` path `{}` on a struct must be a constructed tag",
+                    path.to_token_stream()
+                );
+                quote! {
+                    const _: () = {
+                        let tag: #krate::Tag = #tag;
+                        ::core::assert!(tag.constructed, #message);
+                    };
+                }
+            }
+            _ => TokenStream::new(),
+        }
+    }
 
-        Self { name, tag, slice }
+    /// Tag of a struct, which is constructed as it wraps the fields, unless
+    /// `#[tlv(primitive)]` is set
+    fn container_tag(&self) -> Option<Tag> {
+        match &self.tag {
+            Some(Tag::Ber(tag)) => {
+                let mut tag = *tag;
+                tag.constructed = !self.primitive;
+                Some(tag.into())
+            }
+            tag => tag.clone(),
+        }
     }
 }
 
-fn extract_attrs_optional_tag(name: &Ident, attrs: &[Attribute]) -> (Option<Tag>, bool) {
+/// Parse the `#[tlv(...)]` attributes of a struct or field.
+///
+/// Several attributes accumulate as if they were a single one, e.g. `#[tlv(application)]`
+/// followed by `#[tlv(number = "0x2f")]` is `#[tlv(application, number = "0x2f")]`; a repeated
+/// setting keeps its last value. A `simple` tag cannot be combined with BER tag settings, in any
+/// order, and neither can a `tag` path. Malformed settings, e.g. out of range tag numbers, are
+/// returned as compile errors in [`TlvAttrs::errors`].
+fn extract_attrs_optional_tag(name: &Ident, attrs: &[Attribute]) -> TlvAttrs {
     let mut tag = Tag::default();
     let mut tag_number_is_set = false;
+    let mut ber_tag_is_set = false;
+    let mut simple_tag_is_set = false;
+    let mut tag_path = None;
     let mut slice = false;
+    let mut optional = false;
+    let mut allow_duplicate = false;
+    let mut skip = false;
+    let mut ignore_unknown = false;
+    let mut transparent = false;
+    let mut primitive = false;
+    let mut krate = None;
+    let mut errors = TokenStream::new();
 
     for attr in attrs {
         if !attr.path().is_ident("tlv") {
             continue;
         }
 
-        attr.parse_nested_meta(|meta| {
-            let path = meta.path;
+        let parsed = attr.parse_nested_meta(|meta| {
+            let path = &meta.path;
+            if [
+                "universal",
+                "application",
+                "context",
+                "private",
+                "constructed",
+                "primitive",
+                "number",
+            ]
+            .iter()
+            .any(|ident| path.is_ident(ident))
+            {
+                ber_tag_is_set = true;
+            } else if path.is_ident("simple") {
+                simple_tag_is_set = true;
+            }
+
             if path.is_ident("slice") {
                 slice = true;
+            } else if path.is_ident("optional") {
+                optional = true;
+            } else if path.is_ident("allow_duplicate") {
+                allow_duplicate = true;
+            } else if path.is_ident("skip") {
+                skip = true;
+            } else if path.is_ident("ignore_unknown") {
+                ignore_unknown = true;
+            } else if path.is_ident("transparent") {
+                transparent = true;
+            } else if path.is_ident("tag") {
+                tag_path = Some(parse_lit_str(&meta)?.parse::<Path>()?);
+            } else if path.is_ident("crate") {
+                krate = Some(parse_lit_str(&meta)?.parse::<Path>()?);
             } else if path.is_ident("universal") {
                 tag = {
                     let mut tag = if let Tag::Ber(tag) = tag {
@@ -196,6 +555,7 @@ fn extract_attrs_optional_tag(name: &Ident, attrs: &[Attribute]) -> (Option<Tag>
                     tag.into()
                 };
             } else if path.is_ident("constructed") {
+                primitive = false;
                 tag = {
                     let mut tag = if let Tag::Ber(tag) = tag {
                         tag
@@ -206,6 +566,7 @@ fn extract_attrs_optional_tag(name: &Ident, attrs: &[Attribute]) -> (Option<Tag>
                     tag.into()
                 };
             } else if path.is_ident("primitive") {
+                primitive = true;
                 tag = {
                     let mut tag = if let Tag::Ber(tag) = tag {
                         tag
@@ -217,16 +578,7 @@ fn extract_attrs_optional_tag(name: &Ident, attrs: &[Attribute]) -> (Option<Tag>
                 };
             } else if path.is_ident("number") {
                 tag = {
-                    if !meta.input.peek(Token![=]) || !meta.input.peek2(LitStr) {
-                        panic!("Malformed TLV attribute");
-                    }
-                    let _: Token![=] = meta.input.parse().expect("unreachable");
-                    let lit_str: LitStr = meta.input.parse().expect("unreachable");
-
-                    let possibly_with_prefix = lit_str.value();
-                    let without_prefix = possibly_with_prefix.trim_start_matches("0x");
-                    let tag_number = u16::from_str_radix(without_prefix, 16)
-                        .expect("tag values must be between one and 254");
+                    let tag_number = parse_tag_number(&meta, 0, 0x3FFF)?;
                     let mut tag = if let Tag::Ber(tag) = tag {
                         tag
                     } else {
@@ -238,16 +590,7 @@ fn extract_attrs_optional_tag(name: &Ident, attrs: &[Attribute]) -> (Option<Tag>
                 }
             } else if path.is_ident("simple") {
                 tag = {
-                    if !meta.input.peek(Token![=]) || !meta.input.peek2(LitStr) {
-                        panic!("Malformed TLV attribute");
-                    }
-                    let _: Token![=] = meta.input.parse().expect("unreachable");
-                    let lit_str: LitStr = meta.input.parse().expect("unreachable");
-
-                    let possibly_with_prefix = lit_str.value();
-                    let without_prefix = possibly_with_prefix.trim_start_matches("0x");
-                    let tag_number = u8::from_str_radix(without_prefix, 16)
-                        .expect("tag values must be between one and 254");
+                    let tag_number = parse_tag_number(&meta, 1, 0xFE)? as u8;
                     let mut tag = if let Tag::Simple(tag) = tag {
                         tag
                     } else {
@@ -258,11 +601,13 @@ fn extract_attrs_optional_tag(name: &Ident, attrs: &[Attribute]) -> (Option<Tag>
                     tag.into()
                 };
             } else {
-                panic!("unknown `tlv` attribute for field `{}`: {:?}", name, path);
+                return Err(meta.error(format!("unknown `tlv` attribute for `{}`", name)));
             }
             Ok(())
-        })
-        .unwrap();
+        });
+        if let Err(error) = parsed {
+            errors.extend(error.to_compile_error());
+        }
 
         // match attr.parse_meta().expect("error parsing `tlv` attribute") {
         //     Meta::List(MetaList { nested, .. }) if !nested.is_empty() => {
@@ -391,18 +736,111 @@ fn extract_attrs_optional_tag(name: &Ident, attrs: &[Attribute]) -> (Option<Tag>
         // }
     }
 
-    if tag_number_is_set {
-        (Some(tag), slice)
-    } else {
-        (None, slice)
+    if ber_tag_is_set && simple_tag_is_set {
+        errors.extend(
+            syn::Error::new_spanned(
+                name,
+                format!(
+                    "`simple` tag for `{}` cannot be combined with a class, `number`, `constructed` or `primitive`",
+                    name
+                ),
+            )
+            .to_compile_error(),
+        );
     }
+
+    if tag_path.is_some() && (ber_tag_is_set || simple_tag_is_set) {
+        errors.extend(
+            syn::Error::new_spanned(
+                name,
+                format!(
+                    "`tag` path for `{}` cannot be combined with a class, `number`, `constructed`, `primitive` or `simple`",
+                    name
+                ),
+            )
+            .to_compile_error(),
+        );
+    }
+
+    let tag = match tag_path {
+        Some(path) => Some(Tag::Path(TagPath(path))),
+        None if tag_number_is_set => Some(tag),
+        None => None,
+    };
+
+    TlvAttrs {
+        tag,
+        slice,
+        optional,
+        allow_duplicate,
+        skip,
+        ignore_unknown,
+        transparent,
+        primitive,
+        krate,
+        errors,
+    }
+}
+
+/// Parse the string of a `key = "..."` setting
+fn parse_lit_str(meta: &ParseNestedMeta<'_>) -> syn::Result<LitStr> {
+    meta.value()?.parse()
 }
 
-fn extract_attrs(name: &Ident, attrs: &[Attribute]) -> (Tag, bool) {
-    let (tag, slice) = extract_attrs_optional_tag(name, attrs);
+/// Parse a tag number set as `key = "..."`, in hexadecimal with an optional `0x` prefix,
+/// checking it is within `min..=max`
+fn parse_tag_number(meta: &ParseNestedMeta<'_>, min: u16, max: u16) -> syn::Result<u16> {
+    let lit_str = parse_lit_str(meta)?;
+    let value = lit_str.value();
+    u32::from_str_radix(value.trim_start_matches("0x"), 16)
+        .ok()
+        .filter(|number| (u32::from(min)..=u32::from(max)).contains(number))
+        .map(|number| number as u16)
+        .ok_or_else(|| {
+            syn::Error::new_spanned(
+                &lit_str,
+                format!(
+                    "tag numbers must be hexadecimal, from {:#x} to {:#x}",
+                    min, max
+                ),
+            )
+        })
+}
+
+/// Lifetime of the decoded bytes in a `Decodable` impl, and the generics to declare on it
+///
+/// A struct borrowing from the decoded bytes, e.g. with a `&'a str` field, does so through
+/// its lifetime parameter, which comes with `@Self`; other structs get a fresh one.
+fn decoder_lifetime(
+    s: &Structure<'_>,
+    name: &Ident,
+) -> Result<(Lifetime, TokenStream), TokenStream> {
+    let mut lifetimes = s.ast().generics.lifetimes();
+    match (lifetimes.next(), lifetimes.next()) {
+        (None, _) => {
+            let lifetime = Lifetime::new("'a", name.span());
+            Ok((lifetime.clone(), quote!(<#lifetime>)))
+        }
+        (Some(param), None) => Ok((param.lifetime.clone(), TokenStream::new())),
+        (Some(_), Some(param)) => Err(syn::Error::new_spanned(
+            param,
+            "deriving `Decodable` supports at most one lifetime parameter",
+        )
+        .to_compile_error()),
+    }
+}
 
-    if let Some(tag) = tag {
-        (tag, slice)
+fn extract_attrs(name: &Ident, attrs: &[Attribute]) -> (Tag, TlvAttrs) {
+    let attrs = extract_attrs_optional_tag(name, attrs);
+
+    if let Some(tag) = attrs.tag.clone() {
+        (tag, attrs)
+    } else if !attrs.errors.is_empty() {
+        // the tag may be missing due to a malformed setting, which is reported instead
+        (Tag::default(), attrs)
+    } else if attrs.skip {
+        // skipped fields are neither encoded nor decoded
+        (Tag::default(), attrs)
     } else {
         panic!("BER-TLV tag missing for `{}`", name);
     }
@@ -0,0 +1,111 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{Attribute, DataStruct, Ident, Index, Member, Path, Type};
+use synstructure::Structure;
+
+use crate::{decoder_lifetime, extract_attrs_optional_tag};
+
+/// Derive Decodable and Encodable on a single-field struct, delegating to the field
+pub(crate) struct DeriveTransparent {
+    /// Path to the `flexiber` crate
+    krate: Path,
+
+    /// The field, by name or index
+    member: Member,
+
+    /// Type of the field
+    ty: Type,
+}
+
+impl DeriveTransparent {
+    /// Find the field, or return a compile error if the struct is tagged or has not exactly one field
+    fn new(data: &DataStruct, name: &Ident, attrs: &[Attribute]) -> Result<Self, TokenStream> {
+        let tlv_attrs = extract_attrs_optional_tag(name, attrs);
+        if !tlv_attrs.errors.is_empty() {
+            return Err(tlv_attrs.errors);
+        }
+        if tlv_attrs.tag.is_some() {
+            return Err(syn::Error::new_spanned(
+                name,
+                "`transparent` structs are encoded as their field, without a tag of their own",
+            )
+            .to_compile_error());
+        }
+
+        let mut fields = data.fields.iter();
+        let field = match (fields.next(), fields.next()) {
+            (Some(field), None) => field,
+            _ => {
+                return Err(syn::Error::new_spanned(
+                    name,
+                    "`transparent` requires a struct with exactly one field",
+                )
+                .to_compile_error())
+            }
+        };
+        let member = match &field.ident {
+            Some(ident) => Member::Named(ident.clone()),
+            None => Member::Unnamed(Index::from(0)),
+        };
+
+        Ok(Self {
+            krate: tlv_attrs.crate_path(),
+            member,
+            ty: field.ty.clone(),
+        })
+    }
+
+    pub fn derive_decodable(
+        s: &Structure<'_>,
+        data: &DataStruct,
+        name: &Ident,
+        attrs: &[Attribute],
+    ) -> TokenStream {
+        let state = match Self::new(data, name, attrs) {
+            Ok(state) => state,
+            Err(error) => return error,
+        };
+        let (lifetime, impl_generics) = match decoder_lifetime(s, name) {
+            Ok(lifetime) => lifetime,
+            Err(error) => return error,
+        };
+        let krate = state.krate;
+        let member = state.member;
+        let ty = state.ty;
+
+        s.gen_impl(quote! {
+            gen impl #impl_generics #krate::Decodable<#lifetime> for @Self {
+                fn decode(decoder: &mut #krate::Decoder<#lifetime>) -> #krate::Result<Self> {
+                    let value = <#ty as #krate::Decodable<#lifetime>>::decode(decoder)?;
+                    ::core::result::Result::Ok(Self { #member: value })
+                }
+            }
+        })
+    }
+
+    pub fn derive_encodable(
+        s: &Structure<'_>,
+        data: &DataStruct,
+        name: &Ident,
+        attrs: &[Attribute],
+    ) -> TokenStream {
+        let state = match Self::new(data, name, attrs) {
+            Ok(state) => state,
+            Err(error) => return error,
+        };
+        let krate = state.krate;
+        let member = state.member;
+
+        s.gen_impl(quote! {
+            gen impl #krate::Encodable for @Self {
+                fn encoded_length(&self) -> #krate::Result<#krate::Length> {
+                    #krate::Encodable::encoded_length(&self.#member)
+                }
+
+                fn encode(&self, encoder: &mut #krate::Encoder<'_>) -> #krate::Result<()> {
+                    #krate::Encodable::encode(&self.#member, encoder)
+                }
+            }
+        })
+    }
+}
@@ -0,0 +1,144 @@
+use crate::{Decodable, Decoder, Encodable, Encoder, ErrorKind, Length, Result};
+use core::convert::{TryFrom, TryInto};
+
+/// Fixed-size bitmap of `N` bytes, e.g. a set of flags with named bits.
+///
+/// Bits are numbered most significant bit first, like [`BitString`](crate::BitString):
+/// bit 0 is `0x80` of the first byte, bit 8 is `0x80` of the second one.
+/// The content is exactly the `N` bytes.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Bitmap<const N: usize> {
+    bytes: [u8; N],
+}
+
+impl<const N: usize> Bitmap<N> {
+    /// Create a bitmap with all bits cleared.
+    pub const fn new() -> Self {
+        Self { bytes: [0; N] }
+    }
+
+    /// Number of bits in this bitmap.
+    pub const fn bit_len(&self) -> usize {
+        N * 8
+    }
+
+    /// Borrow the bytes containing the bits.
+    pub fn as_bytes(&self) -> &[u8; N] {
+        &self.bytes
+    }
+
+    /// Is the given bit set?
+    ///
+    /// Panics if `bit` is not less than [`Bitmap::bit_len`].
+    pub fn get(&self, bit: usize) -> bool {
+        self.bytes[bit / 8] & Self::mask(bit) != 0
+    }
+
+    /// Set the given bit.
+    ///
+    /// Panics if `bit` is not less than [`Bitmap::bit_len`].
+    pub fn set(&mut self, bit: usize) {
+        self.bytes[bit / 8] |= Self::mask(bit);
+    }
+
+    /// Clear the given bit.
+    ///
+    /// Panics if `bit` is not less than [`Bitmap::bit_len`].
+    pub fn clear(&mut self, bit: usize) {
+        self.bytes[bit / 8] &= !Self::mask(bit);
+    }
+
+    fn mask(bit: usize) -> u8 {
+        0x80 >> (bit % 8)
+    }
+}
+
+impl<const N: usize> Default for Bitmap<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const N: usize> From<[u8; N]> for Bitmap<N> {
+    fn from(bytes: [u8; N]) -> Self {
+        Self { bytes }
+    }
+}
+
+impl<const N: usize> From<Bitmap<N>> for [u8; N] {
+    fn from(bitmap: Bitmap<N>) -> Self {
+        bitmap.bytes
+    }
+}
+
+impl<const N: usize> Encodable for Bitmap<N> {
+    fn encoded_length(&self) -> Result<Length> {
+        Length::try_from(N)
+    }
+
+    /// Encode this value as BER-TLV using the provided [`Encoder`].
+    fn encode(&self, encoder: &mut Encoder<'_>) -> Result<()> {
+        encoder.bytes(&self.bytes)
+    }
+}
+
+impl<const N: usize> Decodable<'_> for Bitmap<N> {
+    fn decode(decoder: &mut Decoder<'_>) -> Result<Self> {
+        let bytes = decoder.bytes(N)?;
+        match bytes.try_into() {
+            Ok(bytes) => Ok(Self { bytes }),
+            Err(_) => decoder.error(ErrorKind::Truncated),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Bitmap;
+    use crate::{Decoder, Encodable, ErrorKind, Tag, TagLike};
+
+    // PIV PIN usage policy, as in SP 800-73-4 table 4 of the discovery object
+    const PIV_PIN: usize = 1;
+    const GLOBAL_PIN: usize = 2;
+    const PREFERS_GLOBAL_PIN: usize = 10;
+
+    #[test]
+    fn pin_usage_policy() {
+        let mut policy = Bitmap::<2>::new();
+        policy.set(PIV_PIN);
+        policy.set(GLOBAL_PIN);
+        policy.set(PREFERS_GLOBAL_PIN);
+        assert_eq!(policy.as_bytes(), &[0x60, 0x20]);
+        assert!(policy.get(GLOBAL_PIN) && !policy.get(0) && !policy.get(15));
+
+        policy.clear(PREFERS_GLOBAL_PIN);
+        assert_eq!(<[u8; 2]>::from(policy), [0x60, 0x00]);
+
+        let mut buf = [0u8; 8];
+        let tag = Tag::application(0x2F);
+        let policy = Bitmap::from([0x40u8, 0x10]);
+        let encoded = tag.with_value(&policy).encode_to_slice(&mut buf).unwrap();
+        assert_eq!(encoded, &[0x5F, 0x2F, 2, 0x40, 0x10]);
+
+        let mut decoder = Decoder::new(encoded);
+        let decoded: Bitmap<2> = decoder.decode_tagged_value(tag).unwrap();
+        assert!(decoded.get(PIV_PIN) && !decoded.get(GLOBAL_PIN) && decoded.get(11));
+    }
+
+    #[test]
+    fn exact_length() {
+        let tag = Tag::OCTET_STRING;
+        let mut decoder = Decoder::new(&[0x04, 1, 0xFF]);
+        assert_eq!(
+            decoder
+                .decode_tagged_value::<_, Bitmap<2>>(tag)
+                .unwrap_err()
+                .kind(),
+            ErrorKind::Truncated
+        );
+
+        let mut decoder = Decoder::new(&[0x04, 3, 0xFF, 0xFF, 0xFF]);
+        assert!(decoder.decode_tagged_value::<_, Bitmap<2>>(tag).is_err());
+    }
+}
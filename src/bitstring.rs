@@ -0,0 +1,123 @@
+use crate::{
+    header::Header, Encodable, Encoder, Error, ErrorKind, Length, Result, Tag, TagLike, Tagged,
+    TaggedSlice,
+};
+use core::convert::TryFrom;
+
+/// ASN.1 BIT STRING, borrowing its bytes.
+///
+/// The content is a leading octet with the number of unused bits in the last byte,
+/// followed by the bytes themselves.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct BitString<'a> {
+    /// Bytes containing the bits, most significant bit first
+    bytes: &'a [u8],
+
+    /// Number of unused (trailing) bits in the last byte
+    unused_bits: u8,
+}
+
+impl<'a> BitString<'a> {
+    /// Create a new [`BitString`], ignoring the last `unused_bits` bits of `bytes`.
+    ///
+    /// Returns [`ErrorKind::InvalidValue`] if `unused_bits` is not in `0..=7`,
+    /// or not zero for an empty bit string.
+    pub fn new(bytes: &'a [u8], unused_bits: u8) -> Result<Self> {
+        if unused_bits > 7 || (bytes.is_empty() && unused_bits != 0) {
+            return Err(ErrorKind::InvalidValue { tag: None }.into());
+        }
+        Ok(Self { bytes, unused_bits })
+    }
+
+    /// Borrow the bytes containing the bits, including the unused ones.
+    pub fn as_bytes(&self) -> &'a [u8] {
+        self.bytes
+    }
+
+    /// Number of unused bits in the last byte.
+    pub fn unused_bits(&self) -> u8 {
+        self.unused_bits
+    }
+
+    /// Number of bits in this bit string.
+    pub fn bit_len(&self) -> usize {
+        self.bytes.len() * 8 - usize::from(self.unused_bits)
+    }
+
+    fn header(&self) -> Result<Header<Tag>> {
+        Header::new(Tag::BIT_STRING, self.bytes.len() + 1)
+    }
+}
+
+impl Tagged for BitString<'_> {
+    fn tag() -> Tag {
+        Tag::BIT_STRING
+    }
+}
+
+impl Encodable for BitString<'_> {
+    fn encoded_length(&self) -> Result<Length> {
+        let header = self.header()?;
+        header.encoded_length()? + header.length
+    }
+
+    fn encode(&self, encoder: &mut Encoder<'_>) -> Result<()> {
+        self.header()?.encode(encoder)?;
+        encoder.byte(self.unused_bits)?;
+        encoder.bytes(self.bytes)
+    }
+}
+
+impl<'a> TryFrom<TaggedSlice<'a>> for BitString<'a> {
+    type Error = Error;
+
+    fn try_from(tagged_slice: TaggedSlice<'a>) -> Result<Self> {
+        tagged_slice.tag().assert_eq(Tag::BIT_STRING)?;
+        match tagged_slice.as_bytes().split_first() {
+            Some((&unused_bits, bytes)) => Self::new(bytes, unused_bits),
+            None => Err(ErrorKind::InvalidValue { tag: None }.into()),
+        }
+        .map_err(|e| e.of_value(Tag::BIT_STRING))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::BitString;
+    use crate::{Decodable, Encodable, ErrorKind, Tag};
+
+    #[test]
+    fn roundtrip() {
+        let mut buf = [0u8; 8];
+
+        // a single set bit
+        let bits = BitString::new(&[0x80], 7).unwrap();
+        assert_eq!(bits.bit_len(), 1);
+        let encoded = bits.encode_to_slice(&mut buf).unwrap();
+        assert_eq!(encoded, &[0x03, 2, 7, 0x80]);
+        assert_eq!(BitString::from_bytes(encoded).unwrap(), bits);
+
+        let empty = BitString::new(&[], 0).unwrap();
+        assert_eq!(empty.bit_len(), 0);
+        let encoded = empty.encode_to_slice(&mut buf).unwrap();
+        assert_eq!(encoded, &[0x03, 1, 0]);
+        assert_eq!(BitString::from_bytes(encoded).unwrap(), empty);
+    }
+
+    #[test]
+    fn invalid() {
+        assert_eq!(
+            BitString::new(&[0xFF], 8).unwrap_err().kind(),
+            ErrorKind::InvalidValue { tag: None }
+        );
+        for encoded in [&[0x03, 0][..], &[0x03, 1, 1], &[0x03, 2, 8, 0x80]] {
+            assert_eq!(
+                BitString::from_bytes(encoded).unwrap_err().kind(),
+                ErrorKind::InvalidValue {
+                    tag: Some(Tag::BIT_STRING)
+                }
+            );
+        }
+    }
+}
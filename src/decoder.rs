@@ -1,8 +1,12 @@
-use crate::{Decodable, ErrorKind, Length, Result, TagLike};
-use core::convert::TryInto;
+use crate::{header::Header, Decodable, Error, ErrorKind, Length, Result, SimpleTag, Tag, TagLike};
+use core::{convert::TryInto, fmt};
 
 /// BER-TLV decoder.
-#[derive(Debug)]
+///
+/// After an error via [`Decoder::error`], the decoder is tainted for good, see
+/// [`Decoder::is_tainted`]. To try an alternative decoding, clone the decoder first
+/// and carry on with the clone whose attempt succeeded.
+#[derive(Clone)]
 pub struct Decoder<'a> {
     /// Byte slice being decoded.
     ///
@@ -10,8 +14,17 @@ pub struct Decoder<'a> {
     /// `None` to prevent further decoding while in a bad state.
     bytes: Option<&'a [u8]>,
 
-    /// Position within the decoded slice.
+    /// Position within the decoded slice, plus `offset`.
     position: Length,
+
+    /// Position of the decoded slice within a larger buffer, see [`Decoder::new_at`].
+    offset: Length,
+
+    /// Whether `0x00`/`0xFF` padding is skipped before optional values.
+    padding: bool,
+
+    /// Whether non-canonical encodings allowed by BER are rejected.
+    strict: bool,
 }
 
 impl<'a> Decoder<'a> {
@@ -20,9 +33,61 @@ impl<'a> Decoder<'a> {
         Self {
             bytes: Some(bytes),
             position: Length::zero(),
+            offset: Length::zero(),
+            padding: false,
+            strict: false,
         }
     }
 
+    /// Create a new decoder for the given byte slice, which starts at `start_offset`
+    /// within a larger buffer, e.g. after the header of an APDU.
+    ///
+    /// Errors report their positions within the larger buffer, rather than within `bytes`.
+    pub fn new_at(bytes: &'a [u8], start_offset: Length) -> Self {
+        Self {
+            position: start_offset,
+            offset: start_offset,
+            ..Self::new(bytes)
+        }
+    }
+
+    /// Skip `0x00` and `0xFF` filler bytes before decoding an `Option<T>` or
+    /// an optional tagged value, as some cards use them to separate objects.
+    ///
+    /// Decoders for nested values do not inherit this setting.
+    pub fn with_padding(mut self) -> Self {
+        self.padding = true;
+        self
+    }
+
+    /// Reject encodings that BER allows but DER does not, e.g. when verifying signatures
+    /// over DER: non-minimal INTEGERs fail with [`ErrorKind::NonCanonicalInteger`],
+    /// non-minimal lengths and tags with [`ErrorKind::NonCanonicalLength`] and
+    /// [`ErrorKind::NonCanonicalTag`]. A zero byte in place of a tag, i.e. the end-of-contents
+    /// marker, fails with [`ErrorKind::InvalidTag`].
+    ///
+    /// Unlike padding, this setting carries over to decoders for nested values
+    /// of the types in this crate.
+    pub fn with_strict(mut self) -> Self {
+        self.strict = true;
+        self
+    }
+
+    /// Was strict decoding enabled via [`Decoder::with_strict`]?
+    pub fn is_strict(&self) -> bool {
+        self.strict
+    }
+
+    /// Skip padding bytes if enabled via [`Decoder::with_padding`].
+    pub(crate) fn skip_padding(&mut self) -> Result<()> {
+        if self.padding {
+            while let Some(0x00 | 0xFF) = self.peek() {
+                self.byte()?;
+            }
+        }
+        Ok(())
+    }
+
     /// Decode a value which impls the [`Decodable`] trait.
     pub fn decode<T: Decodable<'a>>(&mut self) -> Result<T> {
         if self.is_failed() {
@@ -36,32 +101,222 @@ impl<'a> Decoder<'a> {
     }
 
     /// Decode a TaggedValue with tag checked to be as expected, returning the value
+    ///
+    /// The value is decoded from the content of the TLV, without its tag and length.
+    /// It must consume the entire content, otherwise [`ErrorKind::TrailingData`] is returned.
     pub fn decode_tagged_value<T: Decodable<'a> + TagLike, V: Decodable<'a>>(
         &mut self,
         tag: T,
     ) -> Result<V> {
-        let tagged: crate::TaggedSlice<T> = self.decode()?;
-        tagged.tag().assert_eq(tag)?;
-        Self::new(tagged.as_bytes()).decode()
+        self.decode_tagged(tag)?
+            .decode_nested(|decoder| decoder.decode())
+    }
+
+    /// Decode a data object wrapped in the given tag, as PIV wraps the object requested with
+    /// GET DATA in `0x53`, or in `0x7E` for the discovery object.
+    ///
+    /// The wrapper must contain exactly the object, which is decoded with its own tag.
+    pub fn decode_data_object<T: Decodable<'a>>(&mut self, wrapper: Tag) -> Result<T> {
+        self.decode_tagged_value(wrapper)
+    }
+
+    /// Decode a TaggedValue if the next tag is the given one, returning the value
+    ///
+    /// Returns `None` without consuming anything otherwise, or if there is no remaining data.
+    /// Padding is skipped first if enabled with [`Decoder::with_padding`].
+    pub fn decode_optional_tagged_value<T: Decodable<'a> + TagLike, V: Decodable<'a>>(
+        &mut self,
+        tag: T,
+    ) -> Result<Option<V>> {
+        self.skip_padding()?;
+        if self.peek_tag() == Some(tag) {
+            self.decode_tagged_value(tag).map(Some)
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// Decode a TaggedValue if the next tag is the given one, returning the value,
+    /// or the result of `default` without consuming anything otherwise.
+    ///
+    /// Padding is skipped first if enabled with [`Decoder::with_padding`].
+    pub fn decode_tagged_value_or_else<T, V, F>(&mut self, tag: T, default: F) -> Result<V>
+    where
+        T: Decodable<'a> + TagLike,
+        V: Decodable<'a>,
+        F: FnOnce() -> V,
+    {
+        self.decode_optional_tagged_value(tag)
+            .map(|value| value.unwrap_or_else(default))
+    }
+
+    /// Decode consecutive TaggedValues with the given tag, e.g. a repeated field,
+    /// until the next tag is another one or there is no remaining data.
+    ///
+    /// Fails with [`ErrorKind::Overcapacity`] if there are more than `N` of them.
+    #[cfg(feature = "heapless")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "heapless")))]
+    pub fn decode_tagged_sequence<T: Decodable<'a> + TagLike, V: Decodable<'a>, const N: usize>(
+        &mut self,
+        tag: T,
+    ) -> Result<heapless::Vec<V, N>> {
+        let mut values = heapless::Vec::new();
+        while self.peek_tag() == Some(tag) {
+            let value = self.decode_tagged_value(tag)?;
+            if values.push(value).is_err() {
+                return self.error(ErrorKind::Overcapacity { capacity: N });
+            }
+        }
+        Ok(values)
+    }
+
+    /// Peek at the next tag in the decoder without modifying the cursor.
+    ///
+    /// Returns `None` if there is no remaining data or it does not start with a valid tag.
+    pub fn peek_tag<T: Decodable<'a> + TagLike>(&self) -> Option<T> {
+        self.remaining()
+            .ok()
+            .and_then(|bytes| Decoder::new(bytes).decode().ok())
+    }
+
+    /// Skip the next TLV, whatever its tag.
+    pub fn skip_tlv<T: Decodable<'a> + TagLike>(&mut self) -> Result<()> {
+        let _: crate::TaggedSlice<T> = self.decode()?;
+        Ok(())
+    }
+
+    /// Decode the next TLV, whatever its tag, returning all of its bytes including the header.
+    ///
+    /// Combined with [`Encoder::encode_tlv_bytes`], this forwards a TLV without re-encoding it.
+    ///
+    /// [`Encoder::encode_tlv_bytes`]: crate::Encoder::encode_tlv_bytes
+    pub fn decode_raw_tlv<T: Decodable<'a> + TagLike>(&mut self) -> Result<&'a [u8]> {
+        let remaining = self.remaining()?;
+        let start = self.position;
+        self.skip_tlv::<T>()?;
+        let length = (self.position - start)?;
+        Ok(&remaining[..length.to_usize()])
     }
 
     /// Decode a TaggedSlice with tag checked to be as expected, returning the value
     pub fn decode_tagged_slice<T: Decodable<'a> + TagLike>(&mut self, tag: T) -> Result<&'a [u8]> {
-        let tagged: crate::TaggedSlice<T> = self.decode()?;
-        tagged.tag().assert_eq(tag)?;
-        Ok(tagged.as_bytes())
+        self.decode_tagged(tag).map(|tagged| tagged.as_bytes())
+    }
+
+    /// Decode a TaggedSlice with tag checked to be as expected, e.g. to decode its content
+    /// with [`TaggedSlice::decode_nested`](crate::TaggedSlice::decode_nested), which is
+    /// strict if this decoder is.
+    pub fn decode_tagged<T: Decodable<'a> + TagLike>(
+        &mut self,
+        tag: T,
+    ) -> Result<crate::TaggedSlice<'a, T>> {
+        self.expect_tag(tag)?;
+        self.decode()
+    }
+
+    /// Decode a TaggedSlice like [`Decoder::decode_tagged_slice`], rejecting it with
+    /// [`ErrorKind::Overlength`] if its declared length exceeds `max`.
+    ///
+    /// The length is checked before the content is read, so the error is reported at the
+    /// start of the TLV, even if the content is truncated.
+    pub fn decode_tagged_slice_max<T: Decodable<'a> + TagLike>(
+        &mut self,
+        tag: T,
+        max: Length,
+    ) -> Result<&'a [u8]> {
+        self.expect_tag(tag)?;
+        // a malformed header is left for the decoding of the TLV to report
+        if let Ok(header) = self.clone().decode::<Header<T>>() {
+            if header.length > max {
+                return self.error(ErrorKind::Overlength {
+                    needed: header.length.to_usize(),
+                    available: max.to_usize(),
+                });
+            }
+        }
+        self.decode_tagged_slice(tag)
+    }
+
+    /// Check the next tag before decoding the TLV, so that a mismatch is reported at the
+    /// start of the TLV, leaving the cursor there, e.g. to try an alternative.
+    ///
+    /// A missing or malformed tag is left for the decoding of the TLV to report.
+    fn expect_tag<T: Decodable<'a> + TagLike>(&self, tag: T) -> Result<()> {
+        match self.peek_tag::<T>() {
+            Some(actual) => match actual.assert_eq(tag) {
+                Ok(_) => Ok(()),
+                Err(e) => Err(self.make_error(e.kind())),
+            },
+            None => Ok(()),
+        }
+    }
+
+    /// Decode a SIMPLE-TLV data object with the given tag, returning its content.
+    ///
+    /// Like [`Decoder::decode_tagged_slice`] with a [`SimpleTag`], the length follows
+    /// the SIMPLE-TLV rules, see [`SimpleDecoder`](crate::SimpleDecoder).
+    pub fn decode_simple_tagged_slice(&mut self, tag: SimpleTag) -> Result<&'a [u8]> {
+        self.expect_tag(tag)?;
+        let (_, content) = self.decode_simple_tlv()?;
+        Ok(content)
+    }
+
+    /// Decode a BER-TLV length followed by as many content bytes, returning the content,
+    /// e.g. when the tag is handled by another protocol layer.
+    ///
+    /// Fails with [`ErrorKind::Truncated`] if fewer bytes remain than the length announces.
+    pub fn decode_length_value(&mut self) -> Result<&'a [u8]> {
+        let length: Length = self.decode()?;
+        self.read_bytes(length.to_usize())
+    }
+
+    /// Decode the next SIMPLE-TLV data object, returning its tag and content.
+    pub(crate) fn decode_simple_tlv(&mut self) -> Result<(SimpleTag, &'a [u8])> {
+        let tag = self.decode::<SimpleTag>()?;
+        let length = Length::decode_simple(self)?;
+        let available = self.remaining_len()?;
+        if length > available {
+            return self.error(ErrorKind::Length {
+                tag: tag.embedding(),
+                expected: Some(length),
+                actual: available,
+            });
+        }
+        Ok((tag, self.bytes(length)?))
     }
 
     /// Return an error with the given [`ErrorKind`], annotating it with
     /// context about where the error occurred.
+    ///
+    /// This taints the decoder: any further decoding fails with [`ErrorKind::Failed`].
+    /// Use it when the input is definitely malformed, and [`Decoder::make_error`] otherwise.
     pub fn error<T>(&mut self, kind: ErrorKind) -> Result<T> {
         self.bytes.take();
-        Err(kind.at(self.position))
+        Err(self.make_error(kind))
+    }
+
+    /// Create an error with the given [`ErrorKind`] at the current position,
+    /// without tainting the decoder.
+    ///
+    /// This suits errors the caller may recover from, e.g. by trying to decode an
+    /// alternative from the same position, in which case decoding can go on.
+    pub fn make_error(&self, kind: ErrorKind) -> Error {
+        kind.at(self.position)
+    }
+
+    /// Was the decoder tainted by an error, see [`Decoder::error`]?
+    ///
+    /// Taint is terminal: all further decoding fails.
+    /// There is no way to reset it, to recover, decode from a clone made beforehand.
+    pub fn is_tainted(&self) -> bool {
+        self.bytes.is_none()
     }
 
     /// Did the decoding operation fail due to an error?
+    ///
+    /// Same as [`Decoder::is_tainted`].
     pub fn is_failed(&self) -> bool {
-        self.bytes.is_none()
+        self.is_tainted()
     }
 
     /// Finish decoding, returning the given value if there is no
@@ -71,7 +326,7 @@ impl<'a> Decoder<'a> {
             Err(ErrorKind::Failed.at(self.position))
         } else if !self.is_finished() {
             Err(ErrorKind::TrailingData {
-                decoded: self.position,
+                decoded: self.relative_position(),
                 remaining: self.remaining_len()?,
             }
             .at(self.position))
@@ -88,6 +343,14 @@ impl<'a> Decoder<'a> {
         self.remaining().map(|rem| rem.is_empty()).unwrap_or(false)
     }
 
+    /// Read the next `len` raw bytes, e.g. in hand-written [`Decodable`] implementations.
+    ///
+    /// Fails with [`ErrorKind::Truncated`] if fewer bytes remain, after which the decoder
+    /// is failed.
+    pub fn read_bytes(&mut self, len: usize) -> Result<&'a [u8]> {
+        self.bytes(len).or_else(|e| self.error(e.kind()))
+    }
+
     /// Decode a single byte, updating the internal cursor.
     pub(crate) fn byte(&mut self) -> Result<u8> {
         match self.bytes(1u8)? {
@@ -125,18 +388,38 @@ impl<'a> Decoder<'a> {
 
     /// Obtain the remaining bytes in this decoder from the current cursor
     /// position.
-    fn remaining(&self) -> Result<&'a [u8]> {
+    pub(crate) fn remaining(&self) -> Result<&'a [u8]> {
         self.bytes
-            .and_then(|b| b.get(self.position.into()..))
+            .and_then(|b| b.get(self.relative_position().into()..))
             .ok_or_else(|| ErrorKind::Truncated.at(self.position))
     }
 
+    /// Position within the decoded slice, disregarding the offset of [`Decoder::new_at`].
+    fn relative_position(&self) -> Length {
+        self.position.saturating_sub(self.offset)
+    }
+
     /// Get the number of bytes still remaining in the buffer.
-    fn remaining_len(&self) -> Result<Length> {
+    pub(crate) fn remaining_len(&self) -> Result<Length> {
         self.remaining()?.len().try_into()
     }
 }
 
+/// Only shows the position and buffer length, never the buffer contents,
+/// which may be large or secret.
+impl fmt::Debug for Decoder<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Decoder")
+            .field("position", &self.position)
+            .field("offset", &self.offset)
+            .field("buffer_len", &self.bytes.map(|bytes| bytes.len()))
+            .field("failed", &self.is_failed())
+            .field("padding", &self.padding)
+            .field("strict", &self.strict)
+            .finish()
+    }
+}
+
 impl<'a> From<&'a [u8]> for Decoder<'a> {
     fn from(bytes: &'a [u8]) -> Decoder<'a> {
         Decoder::new(bytes)
@@ -145,7 +428,8 @@ impl<'a> From<&'a [u8]> for Decoder<'a> {
 
 #[cfg(test)]
 mod tests {
-    use crate::{Decodable, Tag, TaggedSlice};
+    use super::Decoder;
+    use crate::{Decodable, ErrorKind, Tag, TaggedSlice};
 
     #[test]
     fn zero_length() {
@@ -153,6 +437,292 @@ mod tests {
         let ts = TaggedSlice::from_bytes(buf).unwrap();
         assert_eq!(ts, TaggedSlice::from(Tag::universal(0x5), &[]).unwrap());
     }
+
+    #[test]
+    fn optional_tagged_value() {
+        let buf: &[u8] = &[0x04, 0x02, 1, 2];
+        let mut decoder = Decoder::new(buf);
+        assert_eq!(decoder.peek_tag(), Some(Tag::OCTET_STRING));
+
+        let value: Option<[u8; 2]> = decoder.decode_optional_tagged_value(Tag::NULL).unwrap();
+        assert_eq!(value, None);
+        let value: Option<[u8; 2]> = decoder
+            .decode_optional_tagged_value(Tag::OCTET_STRING)
+            .unwrap();
+        assert_eq!(value, Some([1, 2]));
+
+        assert_eq!(decoder.peek_tag::<Tag>(), None);
+        let value: Option<[u8; 2]> = decoder
+            .decode_optional_tagged_value(Tag::OCTET_STRING)
+            .unwrap();
+        assert_eq!(value, None);
+        assert!(decoder.finish(()).is_ok());
+    }
+
+    #[test]
+    fn tagged_value_or_else() {
+        let buf: &[u8] = &[0x04, 0x02, 1, 2];
+        let mut decoder = Decoder::new(buf);
+
+        let value: [u8; 2] = decoder
+            .decode_tagged_value_or_else(Tag::NULL, || [0xFF; 2])
+            .unwrap();
+        assert_eq!(value, [0xFF; 2]);
+        let value: [u8; 2] = decoder
+            .decode_tagged_value_or_else(Tag::OCTET_STRING, || unreachable!())
+            .unwrap();
+        assert_eq!(value, [1, 2]);
+
+        // also at the end of the data
+        let value: [u8; 2] = decoder
+            .decode_tagged_value_or_else(Tag::OCTET_STRING, Default::default)
+            .unwrap();
+        assert_eq!(value, [0; 2]);
+        assert!(decoder.finish(()).is_ok());
+    }
+
+    #[test]
+    fn skip_tlv() {
+        let buf: &[u8] = &[0x04, 0x02, 1, 2, 0x05, 0x00];
+        let mut decoder = Decoder::new(buf);
+        decoder.skip_tlv::<Tag>().unwrap();
+        assert_eq!(decoder.peek_tag(), Some(Tag::NULL));
+        decoder.skip_tlv::<Tag>().unwrap();
+        assert!(decoder.is_finished());
+
+        let mut decoder = Decoder::new(&[0x04, 0x02, 1]);
+        assert!(decoder.skip_tlv::<Tag>().is_err());
+    }
+
+    #[test]
+    fn read_bytes() {
+        let mut decoder = Decoder::new(&[1, 2, 3]);
+        assert_eq!(decoder.read_bytes(2).unwrap(), &[1, 2]);
+        assert_eq!(decoder.read_bytes(0).unwrap(), &[]);
+        let err = decoder.read_bytes(2).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::Truncated);
+        assert!(decoder.is_failed());
+    }
+
+    #[test]
+    fn tainted() {
+        let mut decoder = Decoder::new(&[0x04, 0x01, 0xAA, 0x05, 0x00]);
+        let fallback = decoder.clone();
+        assert!(decoder.read_bytes(6).is_err());
+        assert!(decoder.is_tainted() && decoder.is_failed());
+        assert_eq!(decoder.read_bytes(1).unwrap_err().kind(), ErrorKind::Failed);
+
+        let mut decoder = fallback;
+        assert!(!decoder.is_tainted());
+        decoder.skip_tlv::<Tag>().unwrap();
+        decoder.decode_tagged_slice(Tag::NULL).unwrap();
+        assert!(decoder.is_finished());
+    }
+
+    #[test]
+    fn data_object() {
+        let wrapper = Tag::application(0x13);
+        let response: &[u8] = &[0x53, 7, 0x30, 5, 0x04, 3, 0xA0, 0x00, 0x00];
+        let mut decoder = Decoder::new(response);
+        let object: TaggedSlice<'_> = decoder.decode_data_object(wrapper).unwrap();
+        assert_eq!(object.tag(), Tag::SEQUENCE);
+        assert_eq!(object.as_bytes(), &response[4..]);
+        assert!(decoder.is_finished());
+
+        // a single object, under the expected wrapper only
+        let mut decoder = Decoder::new(&[0x53, 4, 0x05, 0x00, 0x05, 0x00]);
+        assert!(decoder
+            .decode_data_object::<TaggedSlice<'_>>(wrapper)
+            .is_err());
+        let mut decoder = Decoder::new(&response[2..]);
+        assert!(decoder
+            .decode_data_object::<TaggedSlice<'_>>(wrapper)
+            .is_err());
+    }
+
+    #[test]
+    fn make_error() {
+        let mut decoder = Decoder::new(&[0x05, 0x00, 0x04, 0x01, 0xAA]);
+        decoder.decode_raw_tlv::<Tag>().unwrap();
+
+        // reject the next value, but let the caller decode it otherwise
+        let err = decoder.make_error(ErrorKind::InvalidValue { tag: None });
+        assert_eq!(err.position(), Some(2u8.into()));
+        assert!(!decoder.is_failed());
+        let value: [u8; 1] = decoder.decode_tagged_value(Tag::OCTET_STRING).unwrap();
+        assert_eq!(value, [0xAA]);
+
+        let err = decoder
+            .error::<()>(ErrorKind::InvalidValue { tag: None })
+            .unwrap_err();
+        assert_eq!(err.position(), Some(5u8.into()));
+        assert!(decoder.is_failed());
+    }
+
+    #[test]
+    fn unexpected_tag() {
+        let buf: &[u8] = &[0x05, 0x00, 0x04, 0x01, 0xAA];
+        let mut decoder = Decoder::new(buf);
+        decoder.decode_tagged_slice(Tag::NULL).unwrap();
+
+        // reported at the start of the mismatched TLV, which is not consumed
+        let err = decoder.decode_tagged_slice(Tag::NULL).unwrap_err();
+        assert_eq!(
+            err.kind(),
+            ErrorKind::UnexpectedTag {
+                expected: Some(Tag::NULL),
+                actual: Tag::OCTET_STRING,
+            }
+        );
+        assert_eq!(err.position(), Some(2u8.into()));
+        let err = decoder
+            .decode_tagged_value::<_, [u8; 1]>(Tag::NULL)
+            .unwrap_err();
+        assert_eq!(err.position(), Some(2u8.into()));
+        assert_eq!(
+            decoder.decode_tagged_slice(Tag::OCTET_STRING).unwrap(),
+            &[0xAA]
+        );
+
+        let simple = crate::SimpleTag::try_from(0x01).unwrap();
+        let mut decoder = Decoder::new(&[0x01, 0x00, 0x02, 0x00]);
+        decoder.decode_simple_tagged_slice(simple).unwrap();
+        let err = decoder.decode_simple_tagged_slice(simple).unwrap_err();
+        assert_eq!(err.position(), Some(2u8.into()));
+    }
+
+    #[test]
+    fn decode_tagged_slice_max() {
+        let buf: &[u8] = &[0x04, 0x02, 0xAA, 0xBB, 0x04, 0x82, 0x10, 0x00, 0xCC];
+        let mut decoder = Decoder::new(buf);
+        assert_eq!(
+            decoder
+                .decode_tagged_slice_max(Tag::OCTET_STRING, 2u8.into())
+                .unwrap(),
+            &[0xAA, 0xBB]
+        );
+
+        // the declared length is rejected before the missing content is noticed
+        let err = decoder
+            .decode_tagged_slice_max(Tag::OCTET_STRING, 16u8.into())
+            .unwrap_err();
+        assert_eq!(
+            err.kind(),
+            ErrorKind::Overlength {
+                needed: 0x1000,
+                available: 16,
+            }
+        );
+        assert_eq!(err.position(), Some(4u8.into()));
+        assert!(decoder.is_tainted());
+
+        let mut decoder = Decoder::new(&buf[..4]);
+        let err = decoder
+            .decode_tagged_slice_max(Tag::OCTET_STRING, 1u8.into())
+            .unwrap_err();
+        assert_eq!(err.position(), Some(0u8.into()));
+    }
+
+    #[test]
+    fn decode_length_value() {
+        let mut blob = [0xAAu8; 2 + 0x80 + 2];
+        blob[..2].copy_from_slice(&[0x81, 0x80]);
+        blob[0x82..].copy_from_slice(&[0x01, 0xBB]);
+
+        let mut decoder = Decoder::new(&blob);
+        assert_eq!(decoder.decode_length_value().unwrap(), &blob[2..0x82]);
+        assert_eq!(decoder.decode_length_value().unwrap(), &[0xBB]);
+        assert!(decoder.is_finished());
+
+        let mut decoder = Decoder::new(&[0x03, 0xAA, 0xBB]);
+        let err = decoder.decode_length_value().unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::Truncated);
+        assert!(decoder.is_tainted());
+    }
+
+    #[test]
+    fn new_at() {
+        // GET DATA with a truncated tag list after the five header bytes
+        let apdu: &[u8] = &[
+            0x00, 0xCB, 0x3F, 0xFF, 0x05, 0x05, 0x00, 0x5C, 0x03, 0x5F, 0xC1,
+        ];
+        let data = &apdu[5..];
+
+        let mut decoder = Decoder::new(data);
+        decoder.decode_tagged_slice(Tag::NULL).unwrap();
+        let err = decoder.decode::<crate::TaggedSlice<'_>>().unwrap_err();
+        assert_eq!(err.position(), Some(4u8.into()));
+
+        let mut decoder = Decoder::new_at(data, 5u8.into());
+        assert!(decoder.decode_tagged_slice(Tag::NULL).unwrap().is_empty());
+        let err = decoder.decode::<crate::TaggedSlice<'_>>().unwrap_err();
+        assert_eq!(err.position(), Some(9u8.into()));
+
+        let err = Decoder::new_at(data, 5u8.into()).finish(()).unwrap_err();
+        assert_eq!(
+            err.kind(),
+            ErrorKind::TrailingData {
+                decoded: 0u8.into(),
+                remaining: 6u8.into(),
+            }
+        );
+        assert_eq!(err.position(), Some(5u8.into()));
+    }
+
+    #[test]
+    fn decode_raw_tlv() {
+        let buf: &[u8] = &[0x30, 0x03, 0x04, 0x01, 0xAA, 0x05, 0x00];
+        let mut decoder = Decoder::new(buf);
+        assert_eq!(
+            decoder.decode_raw_tlv::<Tag>().unwrap(),
+            &[0x30, 0x03, 0x04, 0x01, 0xAA]
+        );
+        assert_eq!(decoder.decode_raw_tlv::<Tag>().unwrap(), &[0x05, 0x00]);
+        assert!(decoder.is_finished());
+    }
+
+    #[cfg(feature = "heapless")]
+    #[test]
+    fn tagged_sequence() {
+        let buf: &[u8] = &[0x04, 1, 1, 0x04, 1, 2, 0x05, 0];
+        let mut decoder = Decoder::new(buf);
+        let values: heapless::Vec<[u8; 1], 2> =
+            decoder.decode_tagged_sequence(Tag::OCTET_STRING).unwrap();
+        assert_eq!(values, [[1], [2]]);
+        assert_eq!(decoder.peek_tag(), Some(Tag::NULL));
+
+        // no matching element at all
+        let values: heapless::Vec<[u8; 1], 2> =
+            decoder.decode_tagged_sequence(Tag::OCTET_STRING).unwrap();
+        assert!(values.is_empty());
+
+        let mut decoder = Decoder::new(&buf[..6]);
+        let err = decoder
+            .decode_tagged_sequence::<_, [u8; 1], 1>(Tag::OCTET_STRING)
+            .unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::Overcapacity { capacity: 1 });
+    }
+
+    #[test]
+    fn tagged_value_trailing_content() {
+        let buf: &[u8] = &[0x04, 0x02, 1, 2];
+        let mut decoder = Decoder::new(buf);
+        let value: [u8; 2] = decoder.decode_tagged_value(Tag::OCTET_STRING).unwrap();
+        assert_eq!(value, [1, 2]);
+
+        let buf: &[u8] = &[0x04, 0x05, 1, 2, 3, 4, 5];
+        let mut decoder = Decoder::new(buf);
+        let err = decoder
+            .decode_tagged_value::<_, [u8; 2]>(Tag::OCTET_STRING)
+            .unwrap_err();
+        assert_eq!(
+            err.kind(),
+            ErrorKind::TrailingData {
+                decoded: 2u8.into(),
+                remaining: 3u8.into(),
+            }
+        );
+    }
 }
 // #[cfg(test)]
 // mod tests {
@@ -0,0 +1,86 @@
+use crate::{Encodable, Encoder, ErrorKind, Length, Result};
+use core::convert::TryInto;
+
+/// Content written by a closure, whose length is known upfront.
+///
+/// This allows placing values produced by external code into a [`Container`][crate::Container]
+/// without materializing them in an intermediate buffer. The closure must write exactly
+/// the declared number of bytes, otherwise encoding fails with [`ErrorKind::Underlength`],
+/// whether it writes fewer or more.
+#[derive(Clone, Copy)]
+pub struct DeferredValue<F> {
+    /// Declared length of the content
+    length: Length,
+
+    /// Closure writing the content
+    f: F,
+}
+
+impl<F> DeferredValue<F>
+where
+    F: Fn(&mut Encoder<'_>) -> Result<()>,
+{
+    /// Create a new [`DeferredValue`] writing `length` bytes using `f`.
+    pub fn new(length: Length, f: F) -> Self {
+        Self { length, f }
+    }
+}
+
+impl<F> Encodable for DeferredValue<F>
+where
+    F: Fn(&mut Encoder<'_>) -> Result<()>,
+{
+    fn encoded_length(&self) -> Result<Length> {
+        Ok(self.length)
+    }
+
+    fn encode(&self, encoder: &mut Encoder<'_>) -> Result<()> {
+        let actual_len = encoder.encode_reserved(self.length, &self.f)?;
+
+        if actual_len == self.length {
+            Ok(())
+        } else {
+            encoder.error(ErrorKind::Underlength {
+                expected: self.length,
+                actual: actual_len.try_into()?,
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::DeferredValue;
+    use crate::{Encodable, ErrorKind, Tag, TagLike};
+
+    #[test]
+    fn encode() {
+        let mut buf = [0u8; 16];
+
+        let deferred = DeferredValue::new(3u8.into(), |encoder| encoder.encode(&[1u8, 2, 3]));
+        let tagged = Tag::OCTET_STRING.with_value(&deferred);
+        assert_eq!(tagged.encoded_length().unwrap(), 5usize);
+        assert_eq!(
+            tagged.encode_to_slice(&mut buf).unwrap(),
+            &[0x04, 3, 1, 2, 3]
+        );
+
+        let short = DeferredValue::new(4u8.into(), |encoder| encoder.encode(&[1u8, 2, 3]));
+        assert_eq!(
+            short.encode_to_slice(&mut buf).unwrap_err().kind(),
+            ErrorKind::Underlength {
+                expected: 4u8.into(),
+                actual: 3u8.into()
+            }
+        );
+
+        let long = DeferredValue::new(2u8.into(), |encoder| encoder.encode(&[1u8, 2, 3]));
+        assert_eq!(
+            long.encode_to_slice(&mut buf).unwrap_err().kind(),
+            ErrorKind::Underlength {
+                expected: 2u8.into(),
+                actual: 3u8.into()
+            }
+        );
+    }
+}
@@ -0,0 +1,120 @@
+use crate::{Decodable, Decoder, Encodable, Tag, TaggedSlice};
+use core::fmt;
+
+/// Maximum nesting rendered as a tree, deeper content is shown as hex
+const MAX_DEPTH: usize = 16;
+
+/// Render BER-TLV encoded bytes as an indented tree, e.g. in test assertions.
+///
+/// Each line shows the encoded tag in hex, the length, and for values that are not
+/// rendered as nested TLVs, the content in hex. Constructed values are rendered as nested
+/// TLVs, as are primitive ones whose content is exactly a sequence of TLVs, as PIV data
+/// objects often are. Bytes that are not a well-formed TLV are shown as `invalid`.
+///
+/// With the `alloc` feature, the output can be collected with `to_string`.
+///
+/// ```
+/// # #[cfg(feature = "alloc")] {
+/// use flexiber::hex_dump;
+///
+/// let encoded = [0x30, 5, 0x04, 1, 0xAA, 0x05, 0];
+/// assert_eq!(
+///     hex_dump(&encoded).to_string(),
+///     "30 (5)\n  04 (1): AA\n  05 (0):\n"
+/// );
+/// # }
+/// ```
+pub fn hex_dump(bytes: &[u8]) -> HexDump<'_> {
+    HexDump { bytes }
+}
+
+/// Tree rendering of BER-TLV encoded bytes, see [`hex_dump`].
+#[derive(Copy, Clone, Debug)]
+pub struct HexDump<'a> {
+    bytes: &'a [u8],
+}
+
+impl fmt::Display for HexDump<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_tlvs(f, self.bytes, 0)
+    }
+}
+
+fn write_tlvs(f: &mut fmt::Formatter<'_>, bytes: &[u8], depth: usize) -> fmt::Result {
+    let mut decoder = Decoder::new(bytes);
+    while !decoder.is_finished() {
+        let rest = decoder.remaining().unwrap_or_default();
+        let (raw, tagged) = match decoder
+            .decode_raw_tlv::<Tag>()
+            .and_then(|raw| Ok((raw, TaggedSlice::<Tag>::from_bytes(raw)?)))
+        {
+            Ok(tlv) => tlv,
+            Err(_) => {
+                write!(f, "{:indent$}invalid:", "", indent = 2 * depth)?;
+                return write_hex(f, rest);
+            }
+        };
+
+        let tag_len = tagged
+            .tag()
+            .encoded_length()
+            .map_or(1, |len| len.to_usize());
+        write!(f, "{:indent$}", "", indent = 2 * depth)?;
+        for byte in &raw[..tag_len] {
+            write!(f, "{:02X}", byte)?;
+        }
+        write!(f, " ({})", tagged.length())?;
+
+        let content = tagged.as_bytes();
+        let nested = !content.is_empty()
+            && depth + 1 < MAX_DEPTH
+            && (tagged.tag().constructed || is_tlv_sequence(content));
+        if nested {
+            writeln!(f)?;
+            write_tlvs(f, content, depth + 1)?;
+        } else {
+            write!(f, ":")?;
+            write_hex(f, content)?;
+        }
+    }
+    Ok(())
+}
+
+fn write_hex(f: &mut fmt::Formatter<'_>, bytes: &[u8]) -> fmt::Result {
+    for byte in bytes {
+        write!(f, " {:02X}", byte)?;
+    }
+    writeln!(f)
+}
+
+/// Is `bytes` exactly a sequence of well-formed TLVs?
+fn is_tlv_sequence(bytes: &[u8]) -> bool {
+    let mut decoder = Decoder::new(bytes);
+    while !decoder.is_finished() {
+        if decoder.skip_tlv::<Tag>().is_err() {
+            return false;
+        }
+    }
+    true
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use super::hex_dump;
+    use alloc::string::ToString;
+
+    #[test]
+    fn invalid() {
+        assert_eq!(hex_dump(&[]).to_string(), "");
+        assert_eq!(
+            hex_dump(&[0x04, 1, 0xAA, 0x04, 3, 0xBB]).to_string(),
+            "04 (1): AA\ninvalid: 04 03 BB\n"
+        );
+    }
+
+    #[test]
+    fn long_tags() {
+        let encoded = [0x7F, 0x49, 4, 0x5F, 0x2F, 1, 0x40];
+        assert_eq!(hex_dump(&encoded).to_string(), "7F49 (4)\n  5F2F (1): 40\n");
+    }
+}
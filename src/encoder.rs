@@ -1,8 +1,13 @@
-use crate::{header::Header, Encodable, ErrorKind, Length, Result, Tag};
-use core::convert::{TryFrom, TryInto};
+use crate::{
+    header::Header, Decodable, Encodable, ErrorKind, Length, Result, SimpleTag, Tag, TagLike,
+    TaggedSlice,
+};
+use core::{
+    convert::{TryFrom, TryInto},
+    fmt,
+};
 
 /// BER-TLV encoder.
-#[derive(Debug)]
 pub struct Encoder<'a> {
     /// Buffer into which BER-TLV-encoded message is written
     bytes: Option<&'a mut [u8]>,
@@ -21,7 +26,7 @@ impl<'a> Encoder<'a> {
     }
 
     /// Encode a value which impls the [`Encodable`] trait.
-    pub fn encode<T: Encodable>(&mut self, encodable: &T) -> Result<()> {
+    pub fn encode<T: Encodable + ?Sized>(&mut self, encodable: &T) -> Result<()> {
         if self.is_failed() {
             self.error(ErrorKind::Failed)?;
         }
@@ -32,6 +37,17 @@ impl<'a> Encoder<'a> {
         })
     }
 
+    /// Encode a sequence of values, stopping at the first error.
+    pub fn encode_all<'b, I: IntoIterator<Item = &'b dyn Encodable>>(
+        &mut self,
+        encodables: I,
+    ) -> Result<()> {
+        for encodable in encodables {
+            self.encode(encodable)?;
+        }
+        Ok(())
+    }
+
     /// Return an error with the given [`ErrorKind`], annotating it with
     /// context about where the error occurred.
     pub fn error<T>(&mut self, kind: ErrorKind) -> Result<T> {
@@ -44,6 +60,13 @@ impl<'a> Encoder<'a> {
         self.bytes.is_none()
     }
 
+    /// Number of bytes written to the buffer so far.
+    ///
+    /// This is the length of the slice [`Encoder::finish`] returns, without consuming the encoder.
+    pub fn finished_len(&self) -> Length {
+        self.position
+    }
+
     /// Finish encoding to the buffer, returning a slice containing the data
     /// written to the buffer.
     pub fn finish(self) -> Result<&'a [u8]> {
@@ -66,28 +89,137 @@ impl<'a> Encoder<'a> {
         let expected_len = Length::try_from(encodables)?;
         Header::new(tag, expected_len).and_then(|header| header.encode(self))?;
 
-        let mut nested_encoder = Encoder::new(self.reserve(expected_len)?);
+        let actual_len = self.encode_reserved(expected_len, |nested_encoder| {
+            encodables
+                .iter()
+                .try_for_each(|encodable| encodable.encode(nested_encoder))
+        })?;
+        if actual_len == expected_len {
+            Ok(())
+        } else {
+            self.error(ErrorKind::Length {
+                tag,
+                expected: Some(expected_len),
+                actual: actual_len.try_into()?,
+            })
+        }
+    }
+
+    /// Encode a TLV with the given tag and length, whose content is written by `body`,
+    /// e.g. for a constructed value whose length is known upfront.
+    ///
+    /// Fails with [`ErrorKind::Length`] if `body` writes more or fewer than `len` bytes.
+    pub fn encode_with_length<F>(&mut self, tag: Tag, len: Length, body: F) -> Result<()>
+    where
+        F: FnOnce(&mut Encoder<'_>) -> Result<()>,
+    {
+        Header::new(tag, len).and_then(|header| header.encode(self))?;
 
-        for encodable in encodables {
-            encodable.encode(&mut nested_encoder)?;
+        let actual_len = self.encode_reserved(len, body)?;
+        if actual_len == len {
+            Ok(())
+        } else {
+            self.error(ErrorKind::Length {
+                tag,
+                expected: Some(len),
+                actual: actual_len.try_into()?,
+            })
         }
+    }
 
-        if nested_encoder.finish()?.len() == expected_len.into() {
+    /// Encode a collection of values which impl the [`Encodable`] trait as the content of a
+    /// SIMPLE-TLV data object, whose length follows the SIMPLE-TLV rules rather than BER-TLV.
+    pub fn encode_simple_tagged_collection(
+        &mut self,
+        tag: SimpleTag,
+        encodables: &[&dyn Encodable],
+    ) -> Result<()> {
+        let expected_len = Length::try_from(encodables)?;
+        self.encode(&tag)?;
+        expected_len.encode_simple(self)?;
+
+        let actual_len = self.encode_reserved(expected_len, |nested_encoder| {
+            nested_encoder.encode_all(encodables.iter().copied())
+        })?;
+        if actual_len == expected_len {
             Ok(())
         } else {
-            self.error(ErrorKind::Length { tag })
+            self.error(ErrorKind::Length {
+                tag: tag.embedding(),
+                expected: Some(expected_len),
+                actual: actual_len.try_into()?,
+            })
         }
     }
 
     /// Encode a collection of values which impl the [`Encodable`] trait under a given tag.
     pub fn encode_untagged_collection(&mut self, encodables: &[&dyn Encodable]) -> Result<()> {
         let expected_len = Length::try_from(encodables)?;
-        let mut nested_encoder = Encoder::new(self.reserve(expected_len)?);
 
-        for encodable in encodables {
-            encodable.encode(&mut nested_encoder)?;
+        let actual_len = self.encode_reserved(expected_len, |nested_encoder| {
+            encodables
+                .iter()
+                .try_for_each(|encodable| encodable.encode(nested_encoder))
+        })?;
+        if actual_len == expected_len {
+            Ok(())
+        } else {
+            self.error(ErrorKind::Underlength {
+                expected: expected_len,
+                actual: actual_len.try_into()?,
+            })
         }
-        Ok(())
+    }
+
+    /// Encode content with `content` into the next `len` bytes, returning the number of bytes
+    /// it wrote, or would have written in case it overruns them.
+    ///
+    /// The position is left after the bytes actually written, so reserved bytes that were not
+    /// written never end up in the output, and an error of the caller is reported there.
+    pub(crate) fn encode_reserved<F>(&mut self, len: Length, content: F) -> Result<usize>
+    where
+        F: FnOnce(&mut Encoder<'_>) -> Result<()>,
+    {
+        let start = self.position;
+        let mut nested_encoder = Encoder::new(self.reserve(len)?);
+        let result = content(&mut nested_encoder);
+        let written = nested_encoder.finished_len();
+        self.position = (start + written).or_else(|e| self.error(e.kind()))?;
+
+        match result {
+            Ok(()) => Ok(written.to_usize()),
+            Err(e) => match e.kind() {
+                // the content is confined to `len` bytes, so this is it overrunning them
+                ErrorKind::BufferTooSmall { needed, .. } => Ok(written.to_usize() + needed),
+                kind => self.error(kind),
+            },
+        }
+    }
+
+    /// Encode a data object wrapped in the given tag, as PIV wraps the object sent with
+    /// PUT DATA in `0x53`; see [`Decoder::decode_data_object`] for the reverse.
+    ///
+    /// The object is encoded with its own tag, as the content of the wrapper.
+    ///
+    /// [`Decoder::decode_data_object`]: crate::Decoder::decode_data_object
+    pub fn encode_data_object<T: Encodable + ?Sized>(
+        &mut self,
+        wrapper: Tag,
+        value: &T,
+    ) -> Result<()> {
+        self.encode(&wrapper.with_value(value))
+    }
+
+    /// Copy a pre-serialized TLV verbatim, e.g. one obtained with [`Decoder::decode_raw_tlv`].
+    ///
+    /// `raw` is checked to be a single well-formed TLV first, with BER tags.
+    ///
+    /// [`Decoder::decode_raw_tlv`]: crate::Decoder::decode_raw_tlv
+    pub fn encode_tlv_bytes(&mut self, raw: &[u8]) -> Result<()> {
+        if let Err(e) = TaggedSlice::<Tag>::from_bytes(raw) {
+            return self.error(e.kind());
+        }
+        self.bytes(raw)
     }
 
     /// Encode a single byte into the backing buffer.
@@ -109,13 +241,17 @@ impl<'a> Encoder<'a> {
 
     /// Reserve a portion of the internal buffer, updating the internal cursor
     /// position and returning a mutable slice.
-    fn reserve(&mut self, len: impl TryInto<Length>) -> Result<&mut [u8]> {
+    pub(crate) fn reserve(&mut self, len: impl TryInto<Length>) -> Result<&mut [u8]> {
         let len = len
             .try_into()
             .or_else(|_| self.error(ErrorKind::Overflow))?;
 
-        if len > self.remaining_len()? {
-            self.error(ErrorKind::Overlength)?;
+        let remaining_len = self.remaining_len()?;
+        if len > remaining_len {
+            self.error(ErrorKind::BufferTooSmall {
+                needed: len.into(),
+                available: remaining_len.into(),
+            })?;
         }
 
         let end = (self.position + len).or_else(|e| self.error(e.kind()))?;
@@ -155,9 +291,38 @@ impl<'a> Encoder<'a> {
     }
 }
 
+/// Only shows the position and buffer length, never the buffer contents,
+/// which may be large or secret.
+impl fmt::Debug for Encoder<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Encoder")
+            .field("position", &self.position)
+            .field("buffer_len", &self.bytes.as_ref().map(|bytes| bytes.len()))
+            .field("failed", &self.is_failed())
+            .finish()
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::{Encodable, Tag, TaggedSlice};
+    use super::Encoder;
+    use crate::{Decodable, Encodable, ErrorKind, Length, Tag, TagLike, TaggedSlice};
+
+    #[test]
+    fn empty_tagged_collection() {
+        // exactly enough space for the header
+        let mut buf = [0u8; 2];
+        let mut encoder = Encoder::new(&mut buf);
+        encoder
+            .encode_tagged_collection(Tag::SEQUENCE, &[])
+            .unwrap();
+        let encoded = encoder.finish().unwrap();
+        assert_eq!(encoded, &[0x30, 0x00]);
+
+        let decoded = TaggedSlice::<Tag>::from_bytes(encoded).unwrap();
+        assert_eq!(decoded.tag(), Tag::SEQUENCE);
+        assert!(decoded.is_empty());
+    }
 
     #[test]
     fn zero_length() {
@@ -173,15 +338,187 @@ mod tests {
         );
     }
 
-    // use super::Encoder;
-    // use crate::{ErrorKind, Length};
+    #[test]
+    fn encode_all() {
+        let octets = Tag::OCTET_STRING.with_value(&[1u8, 2]);
+        let null = TaggedSlice::from(Tag::NULL, &[]).unwrap();
+        let nested = Tag::SEQUENCE.with_value(&null);
+        let encodables: [&dyn Encodable; 3] = [&octets, &null, &nested];
+
+        let mut buf = [0u8; 16];
+        let mut encoder = Encoder::new(&mut buf);
+        encoder.encode_all(encodables.iter().copied()).unwrap();
+        assert_eq!(
+            encoder.finish().unwrap(),
+            &[0x04, 2, 1, 2, 0x05, 0, 0x30, 2, 0x05, 0]
+        );
+
+        // stops at the first value not fitting
+        let mut buf = [0u8; 5];
+        let mut encoder = Encoder::new(&mut buf);
+        let err = encoder.encode_all(encodables.iter().copied()).unwrap_err();
+        assert_eq!(
+            err.kind(),
+            ErrorKind::BufferTooSmall {
+                needed: 1,
+                available: 0
+            }
+        );
+        assert!(encoder.is_failed());
+    }
+
+    #[test]
+    fn encode_tlv_bytes() {
+        // forwarded byte for byte, including a non-minimal length encoding
+        let buf: &[u8] = &[0x30, 0x81, 0x03, 0x04, 0x01, 0xAA, 0x05, 0x00];
+        let mut decoder = crate::Decoder::new(buf);
+        let raw = decoder.decode_raw_tlv::<Tag>().unwrap();
+
+        let mut out = [0u8; 8];
+        let mut encoder = Encoder::new(&mut out);
+        encoder.encode_tlv_bytes(raw).unwrap();
+        assert_eq!(encoder.finish().unwrap(), raw);
+
+        for invalid in [&[0x04, 0x02, 0xAA][..], &[0x05, 0x00, 0x05, 0x00], &[]] {
+            let mut encoder = Encoder::new(&mut out);
+            assert!(encoder.encode_tlv_bytes(invalid).is_err());
+            assert!(encoder.is_failed());
+        }
+    }
+
+    #[test]
+    fn encode_with_length() {
+        let body = |encoder: &mut Encoder<'_>| {
+            encoder.encode(&TaggedSlice::from(Tag::OCTET_STRING, &[1, 2])?)?;
+            encoder.encode(&Tag::NULL.with_value(&()))
+        };
+
+        let mut buf = [0u8; 16];
+        let mut encoder = Encoder::new(&mut buf);
+        encoder
+            .encode_with_length(Tag::SEQUENCE, 6u8.into(), body)
+            .unwrap();
+        assert_eq!(
+            encoder.finish().unwrap(),
+            &[0x30, 6, 0x04, 2, 1, 2, 0x05, 0]
+        );
+
+        for len in [5u8, 7] {
+            let mut encoder = Encoder::new(&mut buf);
+            let err = encoder
+                .encode_with_length(Tag::SEQUENCE, len.into(), body)
+                .unwrap_err();
+            assert_eq!(
+                err.kind(),
+                ErrorKind::Length {
+                    tag: Tag::SEQUENCE,
+                    expected: Some(len.into()),
+                    actual: 6u8.into(),
+                }
+            );
+            assert!(encoder.is_failed());
+        }
+    }
+
+    /// Claims 3 bytes, but writes a single one
+    struct UnderWriting;
+
+    impl Encodable for UnderWriting {
+        fn encoded_length(&self) -> crate::Result<Length> {
+            Ok(3u8.into())
+        }
 
-    // #[test]
-    // fn overlength_message() {
-    //     let mut buffer = [];
-    //     let mut encoder = Encoder::new(&mut buffer);
-    //     let err = false.encode(&mut encoder).err().unwrap();
-    //     assert_eq!(err.kind(), ErrorKind::Overlength);
-    //     assert_eq!(err.position(), Some(Length::zero()));
-    // }
+        fn encode(&self, encoder: &mut Encoder<'_>) -> crate::Result<()> {
+            encoder.encode(&0xAAu8)
+        }
+    }
+
+    #[test]
+    fn under_written_collection() {
+        let mut buf = [0xFFu8; 16];
+        let mut encoder = Encoder::new(&mut buf);
+        encoder.encode(&Tag::NULL.with_value(&())).unwrap();
+        let err = encoder
+            .encode_tagged_collection(Tag::SEQUENCE, &[&UnderWriting])
+            .unwrap_err();
+        assert_eq!(
+            err.kind(),
+            ErrorKind::Length {
+                tag: Tag::SEQUENCE,
+                expected: Some(3u8.into()),
+                actual: 1u8.into(),
+            }
+        );
+        // reported after the byte actually written, and no output with the unwritten bytes
+        assert_eq!(err.position(), Some(5u8.into()));
+        assert_eq!(encoder.finished_len(), Length::from(5u8));
+        assert_eq!(encoder.finish().unwrap_err().kind(), ErrorKind::Failed);
+
+        let mut encoder = Encoder::new(&mut buf);
+        let err = encoder
+            .encode_untagged_collection(&[&UnderWriting, &Tag::NULL.with_value(&())])
+            .unwrap_err();
+        assert_eq!(
+            err.kind(),
+            ErrorKind::Underlength {
+                expected: 5u8.into(),
+                actual: 3u8.into(),
+            }
+        );
+        assert_eq!(err.position(), Some(3u8.into()));
+        assert!(encoder.is_failed());
+    }
+
+    #[test]
+    fn data_object() {
+        let wrapper = Tag::application(0x13);
+        let object = TaggedSlice::from(Tag::SEQUENCE, &[0x04, 1, 0xAA]).unwrap();
+
+        let mut buf = [0u8; 16];
+        let mut encoder = Encoder::new(&mut buf);
+        encoder.encode_data_object(wrapper, &object).unwrap();
+        let encoded = encoder.finish().unwrap();
+        assert_eq!(encoded, &[0x53, 5, 0x30, 3, 0x04, 1, 0xAA]);
+
+        let mut decoder = crate::Decoder::new(encoded);
+        let decoded: TaggedSlice<'_> = decoder.decode_data_object(wrapper).unwrap();
+        assert_eq!(decoded, object);
+    }
+
+    #[test]
+    fn finished_len() {
+        let mut buf = [0u8; 8];
+        let mut encoder = Encoder::new(&mut buf);
+        assert_eq!(encoder.finished_len(), Length::zero());
+
+        encoder
+            .encode(&TaggedSlice::from(Tag::OCTET_STRING, &[1, 2, 3]).unwrap())
+            .unwrap();
+        assert_eq!(encoder.finished_len(), Length::from(5u8));
+
+        let len = encoder.finished_len();
+        assert_eq!(encoder.finish().unwrap().len(), len.to_usize());
+    }
+
+    #[test]
+    fn buffer_too_small() {
+        let mut buffer = [0u8; 2];
+        let err = [1u8, 2, 3].encode_to_slice(&mut buffer).unwrap_err();
+        assert_eq!(
+            err.kind(),
+            ErrorKind::BufferTooSmall {
+                needed: 3,
+                available: 2
+            }
+        );
+        assert_eq!(err.position(), Some(Length::zero()));
+    }
+
+    #[test]
+    fn unrepresentable_length() {
+        let mut buffer = [0u8; 2];
+        let mut encoder = Encoder::new(&mut buffer);
+        let err = encoder.reserve(Length::max() + 1).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::Overflow);
+    }
 }
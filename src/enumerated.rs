@@ -0,0 +1,110 @@
+use crate::{
+    Encodable, Encoder, Error, ErrorKind, Length, Result, Tag, TagLike, Tagged, TaggedSlice,
+};
+use core::convert::TryFrom;
+
+/// ASN.1 ENUMERATED value, with a discriminant in `0..=255`.
+///
+/// The content is the minimal two's complement encoding of the discriminant, as for an INTEGER.
+/// Deriving `Decodable` and `Encodable` on a fieldless `#[repr(u8)]` enum goes through this type.
+#[derive(Copy, Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Enumerated(pub u8);
+
+impl Enumerated {
+    /// Minimal two's complement encoding of the discriminant, using `buf` as storage.
+    fn content(self, buf: &mut [u8; 2]) -> &[u8] {
+        buf[1] = self.0;
+        if self.0 < 0x80 {
+            &buf[1..]
+        } else {
+            &buf[..]
+        }
+    }
+}
+
+impl From<u8> for Enumerated {
+    fn from(value: u8) -> Self {
+        Self(value)
+    }
+}
+
+impl From<Enumerated> for u8 {
+    fn from(enumerated: Enumerated) -> u8 {
+        enumerated.0
+    }
+}
+
+impl Tagged for Enumerated {
+    fn tag() -> Tag {
+        Tag::ENUMERATED
+    }
+}
+
+impl Encodable for Enumerated {
+    fn encoded_length(&self) -> Result<Length> {
+        TaggedSlice::from(Tag::ENUMERATED, self.content(&mut [0; 2]))?.encoded_length()
+    }
+
+    fn encode(&self, encoder: &mut Encoder<'_>) -> Result<()> {
+        TaggedSlice::from(Tag::ENUMERATED, self.content(&mut [0; 2]))?.encode(encoder)
+    }
+}
+
+/// Non-minimal encodings and values outside of `0..=255` fail with [`ErrorKind::InvalidValue`].
+impl TryFrom<TaggedSlice<'_>> for Enumerated {
+    type Error = Error;
+
+    fn try_from(tagged_slice: TaggedSlice<'_>) -> Result<Self> {
+        tagged_slice.tag().assert_eq(Tag::ENUMERATED)?;
+        match *tagged_slice.as_bytes() {
+            [value] if value < 0x80 => Ok(Self(value)),
+            [0, value] if value >= 0x80 => Ok(Self(value)),
+            _ => Err(ErrorKind::InvalidValue {
+                tag: Some(Tag::ENUMERATED),
+            }
+            .into()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Enumerated;
+    use crate::{Decodable, Encodable, ErrorKind, Tag};
+
+    #[test]
+    fn roundtrip() {
+        let mut buf = [0u8; 4];
+        for (value, encoded) in [
+            (0u8, &[0x0A, 1, 0][..]),
+            (0x7F, &[0x0A, 1, 0x7F]),
+            (0x80, &[0x0A, 2, 0, 0x80]),
+            (0xFF, &[0x0A, 2, 0, 0xFF]),
+        ] {
+            assert_eq!(
+                Enumerated(value).encode_to_slice(&mut buf).unwrap(),
+                encoded
+            );
+            assert_eq!(Enumerated::from_bytes(encoded).unwrap(), Enumerated(value));
+        }
+    }
+
+    #[test]
+    fn invalid() {
+        for encoded in [
+            &[0x0A, 0][..],
+            &[0x0A, 1, 0x80],
+            &[0x0A, 2, 0, 0x7F],
+            &[0x0A, 2, 1, 0],
+            &[0x0A, 3, 0, 0, 0x80],
+        ] {
+            assert_eq!(
+                Enumerated::from_bytes(encoded).unwrap_err().kind(),
+                ErrorKind::InvalidValue {
+                    tag: Some(Tag::ENUMERATED)
+                }
+            );
+        }
+    }
+}
@@ -2,7 +2,7 @@
 
 // pub use core::str::Utf8Error;
 
-use crate::{Length, Tag};
+use crate::{Class, Length, Tag};
 use core::{convert::Infallible, fmt};
 
 /// Result type.
@@ -10,12 +10,17 @@ pub type Result<T> = core::result::Result<T, Error>;
 
 /// Error type.
 #[derive(Copy, Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub struct Error {
     /// Kind of error
     kind: ErrorKind,
 
     /// Position inside of message where error occurred
     position: Option<Length>,
+
+    /// Tags of the constructed values the error occurred in
+    #[cfg(feature = "tag-path")]
+    tag_path: TagPath,
 }
 
 impl Error {
@@ -24,6 +29,8 @@ impl Error {
         Error {
             kind,
             position: Some(position),
+            #[cfg(feature = "tag-path")]
+            tag_path: TagPath::default(),
         }
     }
 
@@ -43,9 +50,73 @@ impl Error {
         // TODO(tarcieri): better handle length overflows occurring in this calculation?
         let position = (nested_position + self.position.unwrap_or_default()).ok();
 
+        Self { position, ..self }
+    }
+
+    /// Tags of the constructed values the error occurred in, outermost first.
+    ///
+    /// Only the innermost 8 tags are kept.
+    #[cfg(feature = "tag-path")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "tag-path")))]
+    pub fn tag_path(&self) -> &[Tag] {
+        self.tag_path.as_slice()
+    }
+
+    /// For an [`ErrorKind::InvalidValue`] not knowing the tag of the value, record it.
+    pub(crate) fn of_value(mut self, value_tag: Tag) -> Self {
+        if let ErrorKind::InvalidValue { tag: tag @ None } = &mut self.kind {
+            *tag = Some(value_tag);
+        }
+        self
+    }
+
+    /// For errors occurring inside of a constructed value, record its tag.
+    ///
+    /// Does nothing unless the `tag-path` feature is enabled.
+    #[cfg_attr(not(feature = "tag-path"), allow(unused_variables, unused_mut))]
+    pub fn in_tag(mut self, tag: Tag) -> Self {
+        #[cfg(feature = "tag-path")]
+        self.tag_path.push_outer(tag);
+        self
+    }
+}
+
+/// Bounded stack of tags, keeping [`Error`] `Copy`.
+#[cfg(feature = "tag-path")]
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+struct TagPath {
+    /// Tags, outermost first, starting at `MAX_DEPTH - depth`
+    tags: [Tag; TagPath::MAX_DEPTH],
+
+    /// Number of tags recorded
+    depth: u8,
+}
+
+#[cfg(feature = "tag-path")]
+impl TagPath {
+    /// Maximum number of tags recorded
+    const MAX_DEPTH: usize = 8;
+
+    fn push_outer(&mut self, tag: Tag) {
+        let depth = usize::from(self.depth);
+        if depth < Self::MAX_DEPTH {
+            self.tags[Self::MAX_DEPTH - depth - 1] = tag;
+            self.depth += 1;
+        }
+    }
+
+    fn as_slice(&self) -> &[Tag] {
+        &self.tags[Self::MAX_DEPTH - usize::from(self.depth)..]
+    }
+}
+
+#[cfg(feature = "tag-path")]
+impl Default for TagPath {
+    fn default() -> Self {
         Self {
-            kind: self.kind,
-            position,
+            tags: [Tag::NULL; Self::MAX_DEPTH],
+            depth: 0,
         }
     }
 }
@@ -70,6 +141,8 @@ impl From<ErrorKind> for Error {
         Error {
             kind,
             position: None,
+            #[cfg(feature = "tag-path")]
+            tag_path: TagPath::default(),
         }
     }
 }
@@ -101,8 +174,21 @@ impl std::error::Error for ErrorKind {}
 
 /// Error type.
 #[derive(Copy, Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 #[non_exhaustive]
 pub enum ErrorKind {
+    /// Encoded message does not fit in the provided buffer
+    BufferTooSmall {
+        /// Number of bytes needed
+        needed: usize,
+
+        /// Number of bytes available
+        available: usize,
+    },
+
+    /// Key occurs more than once in a map
+    DuplicateKey,
+
     /// Operation failed due to previous error
     Failed,
 
@@ -115,25 +201,86 @@ pub enum ErrorKind {
         byte: u8,
     },
 
-    /// Length greater than u16::MAX
+    /// Tag number is not conventional for the class
+    InvalidTagNumber {
+        /// Class of the tag
+        class: Class,
+
+        /// Tag number
+        number: u16,
+    },
+
+    /// Invalid length encoding, i.e. indefinite
     InvalidLength,
 
+    /// Value is not valid for the type being decoded (e.g. zero for a `NonZero*` type),
+    /// although it is a well-formed TLV
+    InvalidValue {
+        /// Tag of the value, if known
+        ///
+        /// Decoders of the content leave this `None`, it is filled in when the content was
+        /// decoded from a tagged value, e.g. with [`Decoder::decode_tagged_value`](crate::Decoder::decode_tagged_value).
+        tag: Option<Tag>,
+    },
+
     /// Incorrect length for a given field
     Length {
         /// Tag type of the value being decoded
         tag: Tag,
+
+        /// Expected length, if known (e.g. for fixed-size values)
+        expected: Option<Length>,
+
+        /// Actual length
+        actual: Length,
+    },
+
+    /// Length field uses more than the two subsequent bytes needed for lengths up to 65,535
+    LengthTooLong,
+
+    /// Content of a constructed value is not exactly a sequence of TLVs
+    NestedTrailingData {
+        /// Tag of the constructed value
+        tag: Tag,
+
+        /// Length of the complete TLVs at the start of the content
+        decoded: Length,
+
+        /// Length of the truncated or malformed data following them
+        remaining: Length,
     },
 
     // /// Message is not canonically encoded
     // Noncanonical,
+    /// INTEGER is not minimally encoded, which strict decoding rejects
+    NonCanonicalInteger,
+
+    /// Length is not minimally encoded, which strict decoding rejects
+    NonCanonicalLength,
+
+    /// Tag is not minimally encoded, which strict decoding rejects
+    NonCanonicalTag,
+
+    /// Malformed OID
+    Oid,
+
+    /// More elements than fit in a bounded collection
+    Overcapacity {
+        /// Capacity of the collection
+        capacity: usize,
+    },
 
-    // /// Malformed OID
-    // Oid,
     /// Integer overflow occurred (library bug!)
     Overflow,
 
-    /// Message is longer than BER-TLV's limits support
-    Overlength,
+    /// Message is longer than BER-TLV's limits
+    Overlength {
+        /// Number of bytes needed
+        needed: usize,
+
+        /// Number of bytes available
+        available: usize,
+    },
 
     /// Undecoded trailing data at end of message
     TrailingData {
@@ -147,7 +294,7 @@ pub enum ErrorKind {
     /// Unexpected end-of-message/nested field when decoding
     Truncated,
 
-    /// Encoded message is shorter than the expected length
+    /// Encoded message is shorter or longer than the expected length
     /// (i.e. an `Encodable` impl on a particular type has a buggy `encoded_length`)
     Underlength {
         /// Expected length
@@ -198,17 +345,65 @@ impl ErrorKind {
 impl fmt::Display for ErrorKind {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
+            ErrorKind::BufferTooSmall { needed, available } => write!(
+                f,
+                "buffer too small: needed {} bytes, {} available",
+                needed, available
+            ),
+            ErrorKind::DuplicateKey => write!(f, "duplicate key in map"),
             ErrorKind::Failed => write!(f, "operation failed"),
             ErrorKind::InvalidClass { value } => write!(f, "invalid class {}", value),
-            ErrorKind::InvalidLength => write!(f, "length greater than protocol maximum"),
+            ErrorKind::InvalidLength => write!(f, "invalid length encoding"),
+            ErrorKind::InvalidValue { tag: Some(tag) } => write!(f, "invalid value for {}", tag),
+            ErrorKind::InvalidValue { tag: None } => write!(f, "invalid value"),
             ErrorKind::InvalidTag { byte } => {
                 write!(f, "invalid BER-TLV tag: 0x{:02x}", byte)
             }
-            ErrorKind::Length { tag } => write!(f, "incorrect length for {}", tag),
+            ErrorKind::InvalidTagNumber { class, number } => {
+                write!(f, "invalid tag number {} for class {:?}", number, class)
+            }
+            ErrorKind::Length {
+                tag,
+                expected,
+                actual,
+            } => {
+                write!(f, "incorrect length for {}: ", tag)?;
+
+                if let Some(expected) = expected {
+                    write!(f, "expected {}, ", expected)?;
+                }
+
+                write!(f, "got {}", actual)
+            }
+            ErrorKind::LengthTooLong => {
+                write!(
+                    f,
+                    "length field too long: at most 2 length bytes are supported"
+                )
+            }
+            ErrorKind::NestedTrailingData {
+                tag,
+                decoded,
+                remaining,
+            } => write!(
+                f,
+                "malformed content of {}: {} bytes of TLVs followed by {} bytes",
+                tag, decoded, remaining
+            ),
             // ErrorKind::Noncanonical => write!(f, "DER is not canonically encoded"),
-            // ErrorKind::Oid => write!(f, "malformed OID"),
+            ErrorKind::NonCanonicalInteger => write!(f, "INTEGER is not minimally encoded"),
+            ErrorKind::NonCanonicalLength => write!(f, "length is not minimally encoded"),
+            ErrorKind::NonCanonicalTag => write!(f, "tag is not minimally encoded"),
+            ErrorKind::Oid => write!(f, "malformed OID"),
+            ErrorKind::Overcapacity { capacity } => {
+                write!(f, "more than {} elements", capacity)
+            }
             ErrorKind::Overflow => write!(f, "integer overflow"),
-            ErrorKind::Overlength => write!(f, "BER-TLV message is too long"),
+            ErrorKind::Overlength { needed, available } => write!(
+                f,
+                "BER-TLV message is too long: needed {} bytes, {} available",
+                needed, available
+            ),
             ErrorKind::TrailingData { decoded, remaining } => {
                 write!(
                     f,
@@ -219,7 +414,7 @@ impl fmt::Display for ErrorKind {
             ErrorKind::Truncated => write!(f, "BER-TLV message is truncated"),
             ErrorKind::Underlength { expected, actual } => write!(
                 f,
-                "BER-TLV message too short: expected {}, got {}",
+                "BER-TLV message has the wrong length: expected {}, got {}",
                 expected, actual
             ),
             ErrorKind::UnexpectedTag { expected, actual } => {
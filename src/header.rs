@@ -4,6 +4,8 @@ use crate::{Decodable, Decoder, Encodable, Encoder, ErrorKind, Length, Result, T
 use core::convert::TryInto;
 
 /// BER-TLV headers: tag + length component of TLV-encoded values
+///
+/// The length is coded as the tag type prescribes, see [`TagLike::decode_length`].
 #[derive(Copy, Clone, Debug, Eq, PartialEq)]
 pub(crate) struct Header<T> {
     /// Tag representing the type of the encoded value
@@ -30,16 +32,7 @@ where
     fn decode<'b>(decoder: &'b mut Decoder<'a>) -> Result<Header<T>> {
         let tag = T::decode(decoder)?;
 
-        let length = Length::decode(decoder).map_err(|e| {
-            if e.kind() == ErrorKind::Overlength {
-                ErrorKind::Length {
-                    tag: tag.embedding(),
-                }
-                .into()
-            } else {
-                e
-            }
-        })?;
+        let length = T::decode_length(decoder)?;
 
         Ok(Self { tag, length })
     }
@@ -47,14 +40,14 @@ where
 
 impl<T> Encodable for Header<T>
 where
-    T: Encodable,
+    T: Encodable + TagLike,
 {
     fn encoded_length(&self) -> Result<Length> {
-        self.tag.encoded_length()? + self.length.encoded_length()?
+        self.tag.encoded_length()? + T::length_encoded_length(self.length)?
     }
 
     fn encode(&self, encoder: &mut Encoder<'_>) -> Result<()> {
         self.tag.encode(encoder)?;
-        self.length.encode(encoder)
+        T::encode_length(self.length, encoder)
     }
 }
@@ -1,18 +1,33 @@
 //! Length calculations for encoded ASN.1 DER values
 
-use crate::{Decodable, Decoder, Encodable, Encoder, Error, ErrorKind, Result};
-use core::{convert::TryFrom, fmt, ops::Add};
-
-/// BER-TLV-encoded length.
+use crate::{
+    header::Header, Decodable, Decoder, Encodable, Encoder, Error, ErrorKind, Result, TagLike,
+};
+use core::{
+    convert::TryFrom,
+    fmt,
+    ops::{Add, Sub},
+};
+
+/// Length of the content of a data object.
+///
+/// By definition, in the range `0..=65535`. Two encodings are in use:
 ///
-/// By definition, in the range `0..=65535`
+/// - BER-TLV, the [`Decodable`] and [`Encodable`] impls: a single byte up to 127, otherwise
+///   `0x81` followed by one byte, or `0x82` followed by two big-endian bytes.
+///   Indefinite lengths (`0x80`) and longer forms are rejected.
+/// - SIMPLE-TLV, [`Length::decode_simple`] and [`Length::encode_simple`]: one or three
+///   consecutive bytes. If the first byte is not `0xFF`, then the length field consists of
+///   this single byte encoding a number from zero to 254. If the first byte is `0xFF`, then
+///   the length field consists of the subsequent two bytes interpreted as big-endian integer,
+///   with any value from zero to 65,535.
 ///
-/// The length field consists of one or three consecutive bytes.
-/// - If the first byte is not `0xFF`, then the length field consists of a single byte encoding a number from
-///   zero to 254.
-/// - If the first byte is `0xFF`, then the length field consists of the subsequent two bytes interpreted as
-///   big-endian integer, with any value from zero to 65,535.
-#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, PartialOrd, Ord)]
+/// The encoding is selected by the tag type, see [`TagLike::decode_length`]: data objects with
+/// a [`Tag`](crate::Tag) use BER-TLV lengths, those with a [`SimpleTag`](crate::SimpleTag)
+/// SIMPLE-TLV lengths. PIV labels BER-TLV data objects with one-byte tags, which are decoded as
+/// [`Tag`](crate::Tag)s to keep BER-TLV lengths.
+#[derive(Copy, Clone, Debug, Default, Eq, Hash, PartialEq, PartialOrd, Ord)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub struct Length(pub(crate) u16);
 
 impl Length {
@@ -30,8 +45,80 @@ impl Length {
     pub fn to_usize(self) -> usize {
         self.0.into()
     }
+
+    /// Subtract `other`, returning `None` if it is greater than `self`.
+    pub fn checked_sub(self, other: Length) -> Option<Length> {
+        self.0.checked_sub(other.0).map(Length)
+    }
+
+    /// Subtract `other`, returning zero if it is greater than `self`.
+    pub fn saturating_sub(self, other: Length) -> Length {
+        Length(self.0.saturating_sub(other.0))
+    }
+
+    /// Total length of a TLV with the given tag and content length,
+    /// i.e. the encoded tag and length followed by `content` bytes.
+    pub fn tlv_length<T: TagLike + Encodable>(tag: T, content: Length) -> Result<Length> {
+        Header::new(tag, content)?.encoded_length() + content
+    }
+
+    /// Total length of a SIMPLE-TLV data object with the given content length,
+    /// i.e. the one-byte tag and SIMPLE-TLV length followed by `content` bytes.
+    pub fn simple_tlv_length(content: Length) -> Result<Length> {
+        (Length(1) + content.simple_encoded_length()) + content
+    }
+
+    /// Number of bytes of the BER-TLV encoding of this length.
+    pub(crate) const fn encoded_len(self) -> u16 {
+        match self.0 {
+            0..=0x7F => 1,
+            0x80..=0xFF => 2,
+            0x100..=0xFFFF => 3,
+        }
+    }
+
+    /// Length of the SIMPLE-TLV encoding of this length: one byte up to 254, three otherwise.
+    pub fn simple_encoded_length(self) -> Length {
+        match self.0 {
+            0..=0xFE => Length(1),
+            0xFF..=0xFFFF => Length(3),
+        }
+    }
+
+    /// Encode this length using the SIMPLE-TLV rules.
+    pub fn encode_simple(self, encoder: &mut Encoder<'_>) -> Result<()> {
+        match self.0 {
+            0..=0xFE => encoder.byte(self.0 as u8),
+            0xFF..=0xFFFF => {
+                encoder.byte(SIMPLE_LONG_FORM)?;
+                encoder.bytes(&self.0.to_be_bytes())
+            }
+        }
+    }
+
+    /// Decode a length using the SIMPLE-TLV rules.
+    ///
+    /// Any first byte other than `0xFF` is a length, so unlike the BER-TLV decoding,
+    /// this never fails on well-formed input, unless a strict decoder finds the three-byte
+    /// form used for a length below 255.
+    pub fn decode_simple(decoder: &mut Decoder<'_>) -> Result<Length> {
+        match decoder.byte()? {
+            SIMPLE_LONG_FORM => {
+                let len_hi = decoder.byte()? as u16;
+                let len = (len_hi << 8) | (decoder.byte()? as u16);
+                if decoder.is_strict() && len < SIMPLE_LONG_FORM as u16 {
+                    return Err(ErrorKind::NonCanonicalLength.into());
+                }
+                Ok(Length(len))
+            }
+            len => Ok(len.into()),
+        }
+    }
 }
 
+/// First byte of the three-byte SIMPLE-TLV length form
+const SIMPLE_LONG_FORM: u8 = 0xFF;
+
 /// Calculate the sum of the encoded lengths of the encodables.
 impl<'a> TryFrom<&'a [&'a dyn Encodable]> for Length {
     type Error = Error;
@@ -87,6 +174,23 @@ impl Add<Length> for Result<Length> {
     }
 }
 
+impl Sub for Length {
+    type Output = Result<Self>;
+
+    fn sub(self, other: Self) -> Result<Self> {
+        self.checked_sub(other)
+            .ok_or_else(|| ErrorKind::Overflow.into())
+    }
+}
+
+impl Sub<Length> for Result<Length> {
+    type Output = Self;
+
+    fn sub(self, other: Length) -> Self {
+        self? - other
+    }
+}
+
 impl From<u8> for Length {
     fn from(len: u8) -> Length {
         Length(len as u16)
@@ -121,6 +225,30 @@ impl TryFrom<usize> for Length {
     }
 }
 
+impl PartialEq<u16> for Length {
+    fn eq(&self, other: &u16) -> bool {
+        self.0 == *other
+    }
+}
+
+impl PartialEq<Length> for u16 {
+    fn eq(&self, other: &Length) -> bool {
+        *self == other.0
+    }
+}
+
+impl PartialEq<usize> for Length {
+    fn eq(&self, other: &usize) -> bool {
+        self.to_usize() == *other
+    }
+}
+
+impl PartialEq<Length> for usize {
+    fn eq(&self, other: &Length) -> bool {
+        *self == other.to_usize()
+    }
+}
+
 impl Decodable<'_> for Length {
     fn decode(decoder: &mut Decoder<'_>) -> Result<Length> {
         match decoder.byte()? {
@@ -130,33 +258,36 @@ impl Decodable<'_> for Length {
             // one byte to follow
             0x81 => {
                 let len = decoder.byte()?;
-                // allow non-minimum encodings
+                // allow non-minimum encodings, unless strict
+                if decoder.is_strict() && len < 0x80 {
+                    return Err(ErrorKind::NonCanonicalLength.into());
+                }
                 Ok(len.into())
             }
             0x82 => {
                 let len_hi = decoder.byte()? as u16;
                 let len = (len_hi << 8) | (decoder.byte()? as u16);
-                // allow non-minimum encodings
+                // allow non-minimum encodings, unless strict
+                if decoder.is_strict() && len < 0x100 {
+                    return Err(ErrorKind::NonCanonicalLength.into());
+                }
                 Ok(len.into())
             }
-            _ => {
-                // We specialize to a maximum 3-byte length encoding of length
-                Err(ErrorKind::Overlength.into())
-            }
+            // We specialize to a maximum 3-byte length encoding of length; this includes 0xFF,
+            // which only SIMPLE-TLV lengths use, see `decode_simple`
+            _ => Err(ErrorKind::LengthTooLong.into()),
         }
     }
 }
 
 impl Encodable for Length {
     fn encoded_length(&self) -> Result<Length> {
-        match self.0 {
-            0..=0x7F => Ok(Length(1)),
-            0x80..=0xFF => Ok(Length(2)),
-            0x100..=0xFFFF => Ok(Length(3)),
-        }
+        Ok(Length(self.encoded_len()))
     }
 
     fn encode(&self, encoder: &mut Encoder<'_>) -> Result<()> {
+        // the two-byte form below covers all lengths as long as they fit in a `u16`
+        debug_assert!(self.to_usize() <= Length::max());
         match self.0 {
             0..=0x7F => encoder.byte(self.0 as u8),
             0x80..=0xFF => {
@@ -233,6 +364,186 @@ mod tests {
             &[0x82, 0x01, 0x00],
             Length::from(0x100u16).encode_to_slice(&mut buffer).unwrap()
         );
+
+        // the maximum length uses the longest supported form
+        let max = Length::try_from(Length::max()).unwrap();
+        assert_eq!(max, Length(0xFFFF));
+        assert_eq!(max.encoded_length().unwrap(), Length(3));
+        assert_eq!(
+            &[0x82, 0xFF, 0xFF],
+            max.encode_to_slice(&mut buffer).unwrap()
+        );
+        assert_eq!(Length::from_bytes(&[0x82, 0xFF, 0xFF]).unwrap(), max);
+    }
+
+    #[test]
+    fn decode_invalid() {
+        use crate::ErrorKind;
+
+        let kind = |bytes| Length::from_bytes(bytes).unwrap_err().kind();
+        assert_eq!(kind(&[0x80]), ErrorKind::InvalidLength);
+        assert_eq!(kind(&[0xFF]), ErrorKind::LengthTooLong);
+        assert_eq!(kind(&[0x83, 0x00, 0x01, 0x00]), ErrorKind::LengthTooLong);
+        assert_eq!(kind(&[0x84, 0, 0, 0, 1]), ErrorKind::LengthTooLong);
+    }
+
+    #[test]
+    fn sub() {
+        let one = Length::from(1u8);
+        let two = Length::from(2u8);
+
+        assert_eq!(two.checked_sub(one), Some(one));
+        assert_eq!(one.checked_sub(one), Some(Length::zero()));
+        assert_eq!(one.checked_sub(two), None);
+        assert_eq!(
+            Length::zero().checked_sub(Length::zero()),
+            Some(Length::zero())
+        );
+
+        assert_eq!(two.saturating_sub(one), one);
+        assert_eq!(one.saturating_sub(two), Length::zero());
+        assert_eq!(Length::zero().saturating_sub(one), Length::zero());
+
+        assert_eq!((two - one).unwrap(), one);
+        assert_eq!(
+            (Length::zero() - one).unwrap_err().kind(),
+            crate::ErrorKind::Overflow
+        );
+    }
+
+    #[test]
+    fn tlv_length() {
+        use crate::{Class, SimpleTag, Tag, TagLike};
+        use core::convert::TryFrom;
+
+        let tlv = |tag, content: u16| Length::tlv_length(tag, content.into()).unwrap();
+        assert_eq!(tlv(Tag::universal(0x10), 0), Length::from(2u8));
+        assert_eq!(tlv(Tag::universal(0x10), 0x80), Length::from(0x83u8));
+        assert_eq!(
+            tlv(Tag::from(Class::Application, false, 0x1F), 0x100),
+            Length::from(0x105u16)
+        );
+
+        let simple = SimpleTag::try_from(0x55).unwrap();
+        let value = [0u8; 3];
+        let tagged = simple.with_value(&value);
+        assert_eq!(
+            Length::tlv_length(simple, Length::from(3u8)).unwrap(),
+            tagged.encoded_length().unwrap()
+        );
+
+        assert_eq!(
+            Length::tlv_length(Tag::universal(0x10), Length(u16::MAX))
+                .unwrap_err()
+                .kind(),
+            crate::ErrorKind::Overflow
+        );
+    }
+
+    #[test]
+    fn simple() {
+        use crate::Encoder;
+
+        let mut buffer = [0u8; 3];
+        for (length, encoded) in [
+            (0u16, &[0x00][..]),
+            (0x80, &[0x80]),
+            (0xFE, &[0xFE]),
+            (0xFF, &[0xFF, 0x00, 0xFF]),
+            (0x100, &[0xFF, 0x01, 0x00]),
+        ] {
+            let length = Length(length);
+            let mut encoder = Encoder::new(&mut buffer);
+            length.encode_simple(&mut encoder).unwrap();
+            assert_eq!(encoder.finish().unwrap(), encoded);
+            assert_eq!(length.simple_encoded_length().to_usize(), encoded.len());
+
+            let mut decoder = crate::Decoder::new(encoded);
+            assert_eq!(Length::decode_simple(&mut decoder).unwrap(), length);
+            assert!(decoder.is_finished());
+        }
+
+        assert_eq!(
+            Length::simple_tlv_length(Length(0xFF)).unwrap(),
+            Length(0x103)
+        );
+
+        // the same bytes mean something else in either encoding
+        let mut decoder = crate::Decoder::new(&[0x81, 0x80]);
+        assert_eq!(Length::decode_simple(&mut decoder).unwrap(), Length(0x81));
+        assert_eq!(Length::from_bytes(&[0x81, 0x80]).unwrap(), Length(0x80));
+        let mut decoder = crate::Decoder::new(&[0xFF, 0x00, 0x05]);
+        assert_eq!(Length::decode_simple(&mut decoder).unwrap(), Length(5));
+        assert!(Length::from_bytes(&[0xFF, 0x00, 0x05]).is_err());
+    }
+
+    #[test]
+    fn by_tag_type() {
+        use crate::{header::Header, SimpleTag, Tag, TaggedSlice};
+        use core::convert::TryFrom;
+
+        let simple = SimpleTag::try_from(0x55).unwrap();
+        let content = [0xAAu8; 0xFF];
+        let mut buf = [0u8; 0x104];
+        for (length, simple_header, ber_header) in [
+            (0x7F, &[0x55, 0x7F][..], &[0x04, 0x7F][..]),
+            (0x80, &[0x55, 0x80], &[0x04, 0x81, 0x80]),
+            (0xFE, &[0x55, 0xFE], &[0x04, 0x81, 0xFE]),
+            (0xFF, &[0x55, 0xFF, 0x00, 0xFF], &[0x04, 0x81, 0xFF]),
+        ] {
+            let header = Header::new(simple, length).unwrap();
+            let encoded = header.encode_to_slice(&mut buf).unwrap();
+            assert_eq!(encoded, simple_header);
+            assert_eq!(Header::<SimpleTag>::from_bytes(encoded).unwrap(), header);
+
+            let tagged = TaggedSlice::from(simple, &content[..length]).unwrap();
+            let encoded = tagged.encode_to_slice(&mut buf).unwrap();
+            assert_eq!(&encoded[..simple_header.len()], simple_header);
+            assert_eq!(
+                TaggedSlice::<SimpleTag>::from_bytes(encoded).unwrap(),
+                tagged
+            );
+
+            let tagged = TaggedSlice::from(Tag::OCTET_STRING, &content[..length]).unwrap();
+            let encoded = tagged.encode_to_slice(&mut buf).unwrap();
+            assert_eq!(&encoded[..ber_header.len()], ber_header);
+            assert_eq!(TaggedSlice::<Tag>::from_bytes(encoded).unwrap(), tagged);
+        }
+    }
+
+    #[test]
+    fn compare_integers() {
+        let length = Length::from(0x100u16);
+        assert_eq!(length, 0x100u16);
+        assert_eq!(0x100u16, length);
+        assert_eq!(length, 0x100usize);
+        assert_eq!(0x100usize, length);
+        assert_ne!(length, 0xFFusize);
+        assert_ne!(0x10100usize, length);
+    }
+
+    #[test]
+    fn strict() {
+        use crate::Decoder;
+
+        for encoded in [&[0x81, 0x05][..], &[0x82, 0x00, 0xFF]] {
+            assert!(Length::from_bytes(encoded).is_ok());
+            let err = Decoder::new(encoded).with_strict().decode::<Length>();
+            assert_eq!(
+                err.unwrap_err().kind(),
+                crate::ErrorKind::NonCanonicalLength
+            );
+        }
+        for (encoded, length) in [(&[0x81, 0x80][..], 0x80u16), (&[0x82, 0x01, 0x00], 0x100)] {
+            let mut decoder = Decoder::new(encoded).with_strict();
+            assert_eq!(decoder.decode::<Length>().unwrap(), length);
+        }
+
+        let mut decoder = Decoder::new(&[0xFF, 0x00, 0xFE]).with_strict();
+        let err = Length::decode_simple(&mut decoder).unwrap_err();
+        assert_eq!(err.kind(), crate::ErrorKind::NonCanonicalLength);
+        let mut decoder = Decoder::new(&[0xFF, 0x00, 0xFF]).with_strict();
+        assert_eq!(Length::decode_simple(&mut decoder).unwrap(), 0xFFu16);
     }
 
     #[test]
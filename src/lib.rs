@@ -30,27 +30,95 @@ generate_macros!();
 #[cfg(feature = "derive")]
 pub use flexiber_derive::{Decodable, Encodable};
 
+/// Fields sharing a tag fail to derive `Encodable`, unless `#[tlv(allow_duplicate)]` is set:
+///
+/// ```compile_fail
+/// #[derive(flexiber::Encodable)]
+/// #[tlv(number = "0x12")]
+/// struct Repeated {
+///     #[tlv(slice, number = "0x1")]
+///     first: [u8; 2],
+///     #[tlv(slice, number = "0x1")]
+///     second: [u8; 2],
+/// }
+/// ```
+///
+/// Tags set by `tag` paths are compared by value, whatever the paths:
+///
+/// ```compile_fail
+/// mod tags {
+///     pub const AID: flexiber::Tag = flexiber::Tag::application(0xF);
+/// }
+///
+/// #[derive(flexiber::Encodable)]
+/// #[tlv(number = "0x12")]
+/// struct Repeated {
+///     #[tlv(slice, tag = "tags::AID")]
+///     first: [u8; 2],
+///     #[tlv(slice, tag = "crate::tags::AID")]
+///     second: [u8; 2],
+/// }
+/// # fn main() {}
+/// ```
+#[cfg(all(doctest, feature = "derive"))]
+struct DuplicateTags;
+
+/// A `tag` path on a struct must be constructed, as it wraps the fields:
+///
+/// ```compile_fail
+/// const DISCOVERY: flexiber::Tag = flexiber::Tag::application(0x1E);
+///
+/// #[derive(flexiber::Decodable, flexiber::Encodable)]
+/// #[tlv(tag = "DISCOVERY")]
+/// struct Discovery {
+///     #[tlv(slice, number = "0x1")]
+///     aid: [u8; 2],
+/// }
+/// ```
+#[cfg(all(doctest, feature = "derive"))]
+struct PrimitiveContainerTag;
+
 #[cfg(feature = "std")]
 extern crate std;
 
+mod bitmap;
+mod bitstring;
 mod decoder;
+mod deferred;
+mod dump;
 mod encoder;
+mod enumerated;
 mod error;
 mod header;
 mod length;
+#[cfg(feature = "alloc")]
+mod map;
+mod nested;
+mod oid;
+mod range;
 mod simpletag;
+mod simpletlv;
 mod slice;
 mod tag;
 mod tagged;
 mod traits;
+#[cfg(feature = "uuid")]
+mod uuid;
 
+pub use bitmap::Bitmap;
+pub use bitstring::BitString;
 pub use decoder::Decoder;
+pub use deferred::DeferredValue;
+pub use dump::{hex_dump, HexDump};
 pub use encoder::Encoder;
+pub use enumerated::Enumerated;
 pub use error::{Error, ErrorKind, Result};
 pub use length::Length;
+pub use oid::{Arcs, ObjectIdentifier};
 pub use simpletag::SimpleTag;
+pub use simpletlv::{SimpleDecoder, SimpleEncoder};
 pub use slice::Slice;
-pub use tag::{Class, Tag, TagLike};
+pub use tag::{Class, Tag, TagLike, UniversalKind};
 pub use tagged::{TaggedSlice, TaggedValue};
 #[cfg(feature = "heapless")]
 pub use traits::EncodableHeapless;
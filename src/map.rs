@@ -0,0 +1,112 @@
+use crate::{Decodable, Decoder, Encodable, Encoder, ErrorKind, Length, Result};
+use alloc::collections::BTreeMap;
+
+/// A map is encoded as its entries, each the encoding of the key followed by the one of the
+/// value, typically TLVs themselves.
+///
+/// Entries are sorted by key, so the encoding is canonical regardless of insertion order.
+impl<K, V> Encodable for BTreeMap<K, V>
+where
+    K: Encodable,
+    V: Encodable,
+{
+    fn encoded_length(&self) -> Result<Length> {
+        self.iter().try_fold(Length::zero(), |sum, (key, value)| {
+            (sum + key.encoded_length()?)? + value.encoded_length()?
+        })
+    }
+
+    /// Encode this value as BER-TLV using the provided [`Encoder`].
+    fn encode(&self, encoder: &mut Encoder<'_>) -> Result<()> {
+        for (key, value) in self {
+            encoder.encode(key)?;
+            encoder.encode(value)?;
+        }
+        Ok(())
+    }
+}
+
+/// Decodes entries until the decoder is finished, e.g. the entire content
+/// with [`Decoder::decode_tagged_value`].
+///
+/// Entries may come in any order, but a repeated key fails with [`ErrorKind::DuplicateKey`].
+impl<'a, K, V> Decodable<'a> for BTreeMap<K, V>
+where
+    K: Decodable<'a> + Ord,
+    V: Decodable<'a>,
+{
+    fn decode(decoder: &mut Decoder<'a>) -> Result<Self> {
+        let mut map = BTreeMap::new();
+        while !decoder.is_finished() {
+            let key = decoder.decode()?;
+            let value = decoder.decode()?;
+            if map.insert(key, value).is_some() {
+                return decoder.error(ErrorKind::DuplicateKey);
+            }
+        }
+        Ok(map)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Decodable, Decoder, Encodable, ErrorKind, Tag, TagLike};
+    use alloc::collections::BTreeMap;
+
+    type Config = BTreeMap<[u8; 3], [u8; 4]>;
+
+    // keys are INTEGERs, values are OCTET STRINGs, both as raw TLVs
+    fn key(n: u8) -> [u8; 3] {
+        [0x02, 1, n]
+    }
+
+    fn value(n: u8) -> [u8; 4] {
+        [0x04, 2, n, n]
+    }
+
+    #[test]
+    fn canonical_order() {
+        let mut first = Config::new();
+        first.insert(key(2), value(0xB));
+        first.insert(key(1), value(0xA));
+        let mut second = Config::new();
+        second.insert(key(1), value(0xA));
+        second.insert(key(2), value(0xB));
+
+        let expected: &[u8] = &[0x02, 1, 1, 0x04, 2, 0xA, 0xA, 0x02, 1, 2, 0x04, 2, 0xB, 0xB];
+        let mut buf = [0u8; 16];
+        assert_eq!(first.encoded_length().unwrap().to_usize(), expected.len());
+        assert_eq!(first.encode_to_slice(&mut buf).unwrap(), expected);
+        assert_eq!(second.encode_to_slice(&mut buf).unwrap(), expected);
+    }
+
+    #[test]
+    fn roundtrip() {
+        // out of order entries are accepted
+        let encoded: &[u8] = &[
+            0x30, 14, 0x02, 1, 2, 0x04, 2, 0xB, 0xB, 0x02, 1, 1, 0x04, 2, 0xA, 0xA,
+        ];
+        let mut decoder = Decoder::new(encoded);
+        let map: Config = decoder.decode_tagged_value(Tag::SEQUENCE).unwrap();
+        assert!(decoder.finish(()).is_ok());
+        assert_eq!(map.len(), 2);
+        assert_eq!(map[&key(1)], value(0xA));
+
+        let mut buf = [0u8; 16];
+        let reencoded = Tag::SEQUENCE
+            .with_value(&map)
+            .encode_to_slice(&mut buf)
+            .unwrap();
+        assert_eq!(Config::from_bytes(&reencoded[2..]).unwrap(), map);
+
+        assert!(Config::from_bytes(&[]).unwrap().is_empty());
+    }
+
+    #[test]
+    fn duplicate_key() {
+        let encoded: &[u8] = &[0x02, 1, 1, 0x04, 2, 0xA, 0xA, 0x02, 1, 1, 0x04, 2, 0xB, 0xB];
+        let err = Config::from_bytes(encoded).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::DuplicateKey);
+        assert_eq!(err.position(), Some(14u8.into()));
+    }
+}
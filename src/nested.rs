@@ -1,21 +1,9 @@
-use crate::{Decoder, Encodable, Encoder, ErrorKind, Header, Length, Result, Slice, Tag, TaggedSlice};
+use crate::{Decoder, Encodable, Encoder, ErrorKind, Length, Result, Slice, Tag, TaggedSlice};
 
-/// Obtain the length of an ASN.1 `SEQUENCE` of [`Encodable`] values when
-/// serialized as ASN.1 DER, including the `SEQUENCE` tag and length prefix.
-pub fn encoded_length(/*tag: Tag,*/ encodables: &[&dyn Encodable]) -> Result<Length> {
-    let inner_len = encoded_length_inner(encodables)?;
-    // Header::new(tag, inner_len)?.encoded_length() + inner_len
-    Header::new(crate::tag::MEANINGLESS_TAG, inner_len)?.encoded_length() + inner_len
-}
-
-/// Obtain the inner length of a container of [`Encodable`] values
-/// excluding the tag and length.
-pub(crate) fn encoded_length_inner(encodables: &[&dyn Encodable]) -> Result<Length> {
-    encodables
-        .iter()
-        .fold(Ok(Length::zero()), |sum, encodable| {
-            sum + encodable.encoded_length()?
-        })
+/// Obtain the length of a collection of [`Encodable`] values nested under the given tag,
+/// including the tag and length prefix.
+pub(crate) fn encoded_length(tag: Tag, encodables: &[&dyn Encodable]) -> Result<Length> {
+    Length::tlv_length(tag, Length::try_from(encodables)?)
 }
 
 /// Nested BER-TLV data objects.
@@ -32,7 +20,13 @@ impl<'a> Nested<'a> {
     pub fn new(tag: Tag, slice: &'a [u8]) -> Result<Self> {
         Slice::new(slice)
             .map(|slice| Self { tag, slice })
-            .map_err(|_| ErrorKind::Length { tag }.into())
+            .map_err(|_| {
+                ErrorKind::Overlength {
+                    needed: slice.len(),
+                    available: Length::max(),
+                }
+                .into()
+            })
     }
 
     /// Borrow the inner byte sequence
@@ -40,7 +34,6 @@ impl<'a> Nested<'a> {
         self.slice.as_bytes()
     }
 
-
     /// Get Tag
     pub fn tag(&self) -> Tag {
         self.tag
@@ -67,23 +60,74 @@ impl AsRef<[u8]> for Nested<'_> {
 
 impl<'a> From<TaggedSlice<'a>> for Nested<'a> {
     fn from(tagged_slice: TaggedSlice<'a>) -> Nested<'a> {
-        Self { tag: tagged_slice.tag(), slice: tagged_slice.value }
+        Self {
+            tag: tagged_slice.tag(),
+            slice: tagged_slice.value,
+        }
     }
 }
 
 impl<'a> From<Nested<'a>> for TaggedSlice<'a> {
     fn from(nested: Nested<'a>) -> TaggedSlice<'a> {
-        TaggedSlice { tag: nested.tag(), value: nested.slice }
+        TaggedSlice {
+            tag: nested.tag(),
+            value: nested.slice,
+        }
     }
 }
 
 impl<'a> Encodable for Nested<'a> {
+    /// Computed from the tag and the precomputed length of the inner slice.
     fn encoded_length(&self) -> Result<Length> {
-        TaggedSlice::from(*self).encoded_length()
+        Length::tlv_length(self.tag, self.slice.length())
     }
 
     fn encode(&self, encoder: &mut Encoder<'_>) -> Result<()> {
-        TaggedSlice::from(*self).encode(encoder)
+        TaggedSlice::new(self.tag, self.slice).encode(encoder)
     }
 }
 
+#[cfg(test)]
+mod tests {
+    use super::{encoded_length, Nested};
+    use crate::{Encodable, Length, Tag, TaggedSlice};
+
+    #[test]
+    fn collection_length() {
+        let null = TaggedSlice::from(Tag::NULL, &[]).unwrap();
+        let octets = TaggedSlice::from(Tag::OCTET_STRING, &[0xAA; 0x80]).unwrap();
+        let encodables: [&dyn Encodable; 2] = [&null, &octets];
+
+        // 2 + 3 + 0x80 bytes of content
+        let expected = Length::from(0x85u8);
+        assert_eq!(
+            encoded_length(Tag::SEQUENCE, &encodables).unwrap(),
+            Length::tlv_length(Tag::SEQUENCE, expected).unwrap()
+        );
+        assert_eq!(
+            encoded_length(Tag::SEQUENCE, &encodables).unwrap(),
+            Length::from(0x88u8)
+        );
+        assert_eq!(
+            encoded_length(Tag::SEQUENCE, &[]).unwrap(),
+            Length::from(2u8)
+        );
+    }
+
+    #[test]
+    fn encoded_length_matches_tagged_slice() {
+        let mut buf = [0u8; 0x200];
+        for content in [&[][..], &[0xAA; 0x7F], &[0xAA; 0x80], &[0xAA; 0x100]] {
+            for tag in [Tag::SEQUENCE, Tag::application(0x1F).constructed()] {
+                let nested = Nested::new(tag, content).unwrap();
+                let tagged_slice = TaggedSlice::from(tag, content).unwrap();
+                assert_eq!(
+                    nested.encoded_length().unwrap(),
+                    tagged_slice.encoded_length().unwrap()
+                );
+                let encoded_length = nested.encode_to_slice(&mut buf).unwrap().len();
+                assert_eq!(nested.encoded_length().unwrap().to_usize(), encoded_length);
+            }
+        }
+    }
+}
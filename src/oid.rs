@@ -0,0 +1,312 @@
+use crate::{
+    Encodable, Encoder, Error, ErrorKind, Length, Result, Tag, TagLike, Tagged, TaggedSlice,
+};
+use core::{convert::TryFrom, fmt, str::FromStr};
+
+/// ASN.1 OBJECT IDENTIFIER, stored in its BER encoding without allocations.
+///
+/// The sub-identifiers are encoded in base 128, with the first two arcs combined into one.
+/// Decoding rejects non-minimal sub-identifiers, as well as arcs not fitting in a `u32`,
+/// with [`ErrorKind::Oid`].
+#[derive(Copy, Clone, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct ObjectIdentifier {
+    /// BER encoding, zero-padded after `length`
+    bytes: [u8; Self::MAX_LENGTH],
+
+    /// Length of the BER encoding
+    length: u8,
+}
+
+impl ObjectIdentifier {
+    /// Maximum length of the BER encoding of an object identifier, without tag and length.
+    pub const MAX_LENGTH: usize = 39;
+
+    const fn empty() -> Self {
+        Self {
+            bytes: [0; Self::MAX_LENGTH],
+            length: 0,
+        }
+    }
+
+    /// Parse the BER encoding of an object identifier, i.e. the content of its TLV.
+    pub fn from_ber(bytes: &[u8]) -> Result<Self> {
+        if bytes.is_empty() || bytes.len() > Self::MAX_LENGTH {
+            return Err(ErrorKind::Oid.into());
+        }
+
+        let mut at_start = true;
+        let mut value: u32 = 0;
+        for &byte in bytes {
+            // a sub-identifier must not start with a padding byte
+            if at_start && byte == 0x80 {
+                return Err(ErrorKind::Oid.into());
+            }
+            if value > u32::MAX >> 7 {
+                return Err(ErrorKind::Oid.into());
+            }
+            value = (value << 7) | u32::from(byte & 0x7F);
+            at_start = byte & 0x80 == 0;
+            if at_start {
+                value = 0;
+            }
+        }
+        // the last sub-identifier is truncated
+        if !at_start {
+            return Err(ErrorKind::Oid.into());
+        }
+
+        let mut oid = Self::empty();
+        oid.bytes[..bytes.len()].copy_from_slice(bytes);
+        oid.length = bytes.len() as u8;
+        Ok(oid)
+    }
+
+    /// Create an object identifier from its arcs.
+    ///
+    /// There must be at least two arcs, the first one in `0..=2`, and the second one
+    /// in `0..40` unless the first one is `2`.
+    pub fn from_arcs(arcs: &[u32]) -> Result<Self> {
+        let (first, second, rest) = match arcs {
+            [first, second, rest @ ..] => (*first, *second, rest),
+            _ => return Err(ErrorKind::Oid.into()),
+        };
+        if first > 2 || (first < 2 && second >= 40) {
+            return Err(ErrorKind::Oid.into());
+        }
+        let head = (first * 40).checked_add(second).ok_or(ErrorKind::Oid)?;
+
+        let mut oid = Self::empty();
+        oid.push(head)?;
+        for arc in rest {
+            oid.push(*arc)?;
+        }
+        Ok(oid)
+    }
+
+    /// Append a sub-identifier in base 128.
+    fn push(&mut self, value: u32) -> Result<()> {
+        let bits = 32 - value.leading_zeros() as usize;
+        let digits = core::cmp::max(1, bits.div_ceil(7));
+        let start = self.length as usize;
+        let end = start + digits;
+        let bytes = self.bytes.get_mut(start..end).ok_or(ErrorKind::Oid)?;
+        for (i, byte) in bytes.iter_mut().enumerate() {
+            let shift = 7 * (digits - 1 - i);
+            let continuation = if i + 1 < digits { 0x80 } else { 0 };
+            *byte = ((value >> shift) & 0x7F) as u8 | continuation;
+        }
+        self.length = end as u8;
+        Ok(())
+    }
+
+    /// BER encoding of this object identifier, without tag and length.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.bytes[..self.length as usize]
+    }
+
+    /// Iterate over the arcs of this object identifier.
+    pub fn arcs(&self) -> Arcs<'_> {
+        Arcs {
+            bytes: self.as_bytes(),
+            second: None,
+            started: false,
+        }
+    }
+}
+
+/// Iterator over the arcs of an [`ObjectIdentifier`].
+#[derive(Clone, Debug)]
+pub struct Arcs<'a> {
+    /// Remaining BER encoding
+    bytes: &'a [u8],
+
+    /// Second arc, split off the first sub-identifier
+    second: Option<u32>,
+
+    /// Whether the first sub-identifier was read
+    started: bool,
+}
+
+impl Arcs<'_> {
+    /// Read the next sub-identifier; the encoding was validated on construction.
+    fn subidentifier(&mut self) -> Option<u32> {
+        let mut value = 0;
+        while let Some((&byte, rest)) = self.bytes.split_first() {
+            self.bytes = rest;
+            value = (value << 7) | u32::from(byte & 0x7F);
+            if byte & 0x80 == 0 {
+                return Some(value);
+            }
+        }
+        None
+    }
+}
+
+impl Iterator for Arcs<'_> {
+    type Item = u32;
+
+    fn next(&mut self) -> Option<u32> {
+        if let Some(second) = self.second.take() {
+            return Some(second);
+        }
+        let value = self.subidentifier()?;
+        if self.started {
+            return Some(value);
+        }
+        self.started = true;
+        let (first, second) = match value {
+            0..=39 => (0, value),
+            40..=79 => (1, value - 40),
+            _ => (2, value - 80),
+        };
+        self.second = Some(second);
+        Some(first)
+    }
+}
+
+/// Parse the dotted notation, e.g. `"1.2.840.113549"`.
+impl FromStr for ObjectIdentifier {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let mut arcs = [0u32; Self::MAX_LENGTH + 1];
+        let mut count = 0;
+        for arc in s.split('.') {
+            if arc.is_empty() || !arc.bytes().all(|byte| byte.is_ascii_digit()) {
+                return Err(ErrorKind::Oid.into());
+            }
+            let slot = arcs.get_mut(count).ok_or(ErrorKind::Oid)?;
+            *slot = arc.parse().map_err(|_| ErrorKind::Oid)?;
+            count += 1;
+        }
+        Self::from_arcs(&arcs[..count])
+    }
+}
+
+impl fmt::Display for ObjectIdentifier {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, arc) in self.arcs().enumerate() {
+            if i > 0 {
+                write!(f, ".")?;
+            }
+            write!(f, "{}", arc)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Debug for ObjectIdentifier {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "ObjectIdentifier({})", self)
+    }
+}
+
+impl Tagged for ObjectIdentifier {
+    fn tag() -> Tag {
+        Tag::OBJECT_IDENTIFIER
+    }
+}
+
+impl Encodable for ObjectIdentifier {
+    fn encoded_length(&self) -> Result<Length> {
+        TaggedSlice::from(Tag::OBJECT_IDENTIFIER, self.as_bytes())?.encoded_length()
+    }
+
+    fn encode(&self, encoder: &mut Encoder<'_>) -> Result<()> {
+        TaggedSlice::from(Tag::OBJECT_IDENTIFIER, self.as_bytes())?.encode(encoder)
+    }
+}
+
+impl TryFrom<TaggedSlice<'_>> for ObjectIdentifier {
+    type Error = Error;
+
+    fn try_from(tagged_slice: TaggedSlice<'_>) -> Result<Self> {
+        tagged_slice.tag().assert_eq(Tag::OBJECT_IDENTIFIER)?;
+        Self::from_ber(tagged_slice.as_bytes())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ObjectIdentifier;
+    use crate::{Decodable, Encodable, ErrorKind};
+
+    #[test]
+    fn roundtrip() {
+        let mut buf = [0u8; 16];
+        for (dotted, ber) in [
+            ("1.2.840.113549", &[0x2A, 0x86, 0x48, 0x86, 0xF7, 0x0D][..]),
+            ("2.5.4.3", &[0x55, 0x04, 0x03]),
+            ("2.999.3", &[0x88, 0x37, 0x03]),
+            ("0.0", &[0x00]),
+        ] {
+            let oid: ObjectIdentifier = dotted.parse().unwrap();
+            assert_eq!(oid.as_bytes(), ber);
+            assert_eq!(ObjectIdentifier::from_ber(ber).unwrap(), oid);
+
+            let encoded = oid.encode_to_slice(&mut buf).unwrap();
+            assert_eq!(encoded[..2], [0x06, ber.len() as u8]);
+            assert_eq!(&encoded[2..], ber);
+            assert_eq!(ObjectIdentifier::from_bytes(encoded).unwrap(), oid);
+
+            let mut arcs = [0u32; 4];
+            let mut count = 0;
+            for (slot, arc) in arcs.iter_mut().zip(oid.arcs()) {
+                *slot = arc;
+                count += 1;
+            }
+            assert_eq!(oid.arcs().count(), count);
+            assert_eq!(ObjectIdentifier::from_arcs(&arcs[..count]).unwrap(), oid);
+        }
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn display() {
+        use alloc::format;
+
+        let oid: ObjectIdentifier = "1.2.840.113549.1.1.11".parse().unwrap();
+        assert_eq!(format!("{}", oid), "1.2.840.113549.1.1.11");
+        assert_eq!(
+            format!("{:?}", oid),
+            "ObjectIdentifier(1.2.840.113549.1.1.11)"
+        );
+    }
+
+    #[test]
+    fn invalid() {
+        for ber in [
+            // empty
+            &[][..],
+            // non-minimal sub-identifier
+            &[0x2A, 0x80, 0x01],
+            &[0x80, 0x2A],
+            // truncated sub-identifier
+            &[0x2A, 0x86],
+            // arc exceeding `u32`
+            &[0x2A, 0x90, 0x80, 0x80, 0x80, 0x00],
+        ] {
+            assert_eq!(
+                ObjectIdentifier::from_ber(ber).unwrap_err().kind(),
+                ErrorKind::Oid
+            );
+        }
+
+        for dotted in [
+            "",
+            "1",
+            "3.1",
+            "1.40",
+            "1..2",
+            "1.2.",
+            "+1.2",
+            "1.2.4294967296",
+        ] {
+            assert_eq!(
+                dotted.parse::<ObjectIdentifier>().unwrap_err().kind(),
+                ErrorKind::Oid
+            );
+        }
+    }
+}
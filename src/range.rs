@@ -0,0 +1,189 @@
+use crate::{
+    Container, Decodable, Decoder, Encodable, Encoder, ErrorKind, Length, Result, Tag, Tagged,
+    TaggedSlice,
+};
+use core::ops::Range;
+
+/// Error for INTEGERs which are negative or exceed `u32`
+const INVALID_INTEGER: ErrorKind = ErrorKind::InvalidValue {
+    tag: Some(Tag::INTEGER),
+};
+
+/// Non-negative ASN.1 INTEGER fitting in a `u32`.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+struct Unsigned(u32);
+
+impl Unsigned {
+    /// Minimal two's complement encoding of the value, using `buf` as storage.
+    fn content(self, buf: &mut [u8; 5]) -> &[u8] {
+        buf[1..].copy_from_slice(&self.0.to_be_bytes());
+        let leading_zeros = buf[1..].iter().take_while(|&&byte| byte == 0).count();
+        // keep one byte for zero, and a zero byte before a set high bit
+        let start = 1 + core::cmp::min(leading_zeros, 3);
+        if buf[start] & 0x80 != 0 {
+            &buf[start - 1..]
+        } else {
+            &buf[start..]
+        }
+    }
+
+    /// Parse the content of an INTEGER.
+    ///
+    /// Negative values and values exceeding `u32` fail with [`ErrorKind::InvalidValue`].
+    /// Redundant leading zero bytes are accepted as BER allows, unless `strict` is set,
+    /// failing with [`ErrorKind::NonCanonicalInteger`].
+    fn from_content(bytes: &[u8], strict: bool) -> Result<Self> {
+        match bytes {
+            [] => return Err(INVALID_INTEGER.into()),
+            [first, ..] if first & 0x80 != 0 => return Err(INVALID_INTEGER.into()),
+            [0, next, ..] if strict && next & 0x80 == 0 => {
+                return Err(ErrorKind::NonCanonicalInteger.into())
+            }
+            _ => {}
+        }
+        let leading_zeros = bytes.iter().take_while(|&&byte| byte == 0).count();
+        let magnitude = &bytes[leading_zeros..];
+        if magnitude.len() > 4 {
+            return Err(INVALID_INTEGER.into());
+        }
+        Ok(Self(
+            magnitude
+                .iter()
+                .fold(0, |value, &byte| (value << 8) | u32::from(byte)),
+        ))
+    }
+}
+
+impl Encodable for Unsigned {
+    fn encoded_length(&self) -> Result<Length> {
+        TaggedSlice::from(Tag::INTEGER, self.content(&mut [0; 5]))?.encoded_length()
+    }
+
+    fn encode(&self, encoder: &mut Encoder<'_>) -> Result<()> {
+        TaggedSlice::from(Tag::INTEGER, self.content(&mut [0; 5]))?.encode(encoder)
+    }
+}
+
+impl<'a> Decodable<'a> for Unsigned {
+    fn decode(decoder: &mut Decoder<'a>) -> Result<Self> {
+        let content = decoder.decode_tagged_slice(Tag::INTEGER)?;
+        Self::from_content(content, decoder.is_strict())
+    }
+}
+
+/// A range is encoded as a SEQUENCE of two INTEGERs, its start and its end.
+///
+/// The end is not required to be greater than the start.
+impl Tagged for Range<u32> {
+    fn tag() -> Tag {
+        Tag::SEQUENCE
+    }
+}
+
+impl Container for Range<u32> {
+    fn fields<F, T>(&self, f: F) -> Result<T>
+    where
+        F: FnOnce(&[&dyn Encodable]) -> Result<T>,
+    {
+        f(&[&Unsigned(self.start), &Unsigned(self.end)])
+    }
+}
+
+impl<'a> Decodable<'a> for Range<u32> {
+    fn decode(decoder: &mut Decoder<'a>) -> Result<Self> {
+        decoder
+            .decode_tagged(Tag::SEQUENCE)?
+            .decode_nested(|decoder| {
+                let start = decoder.decode::<Unsigned>()?;
+                let end = decoder.decode::<Unsigned>()?;
+                Ok(start.0..end.0)
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Decodable, Decoder, Encodable, ErrorKind, Tag, TaggedSlice};
+    use core::ops::Range;
+
+    #[test]
+    fn roundtrip() {
+        let mut buf = [0u8; 16];
+        for (range, encoded) in [
+            (0..1, &[0x30, 6, 0x02, 1, 0, 0x02, 1, 1][..]),
+            (0x7F..0x80, &[0x30, 7, 0x02, 1, 0x7F, 0x02, 2, 0, 0x80]),
+            (
+                0x100..u32::MAX,
+                &[0x30, 11, 0x02, 2, 1, 0, 0x02, 5, 0, 0xFF, 0xFF, 0xFF, 0xFF],
+            ),
+        ] {
+            assert_eq!(range.encode_to_slice(&mut buf).unwrap(), encoded);
+            assert_eq!(Range::<u32>::from_bytes(encoded).unwrap(), range);
+        }
+    }
+
+    #[test]
+    fn invalid() {
+        for encoded in [
+            // empty
+            &[0x30, 5, 0x02, 0, 0x02, 1, 1][..],
+            // negative
+            &[0x30, 6, 0x02, 1, 0x80, 0x02, 1, 1],
+            // exceeding `u32`
+            &[0x30, 10, 0x02, 1, 0, 0x02, 5, 1, 0, 0, 0, 0],
+        ] {
+            assert_eq!(
+                Range::<u32>::from_bytes(encoded).unwrap_err().kind(),
+                ErrorKind::InvalidValue {
+                    tag: Some(Tag::INTEGER)
+                }
+            );
+        }
+        // the error keeps its position within the content of the SEQUENCE
+        let encoded: &[u8] = &[0x30, 6, 0x02, 1, 1, 0x02, 1, 0x80];
+        let err = Range::<u32>::from_bytes(encoded).unwrap_err();
+        assert_eq!(err.position(), Some(6u8.into()));
+    }
+
+    #[test]
+    fn non_minimal() {
+        let encoded: &[u8] = &[0x30, 8, 0x02, 2, 0, 1, 0x02, 2, 0, 0x80];
+
+        // BER allows redundant leading zeros
+        assert_eq!(Range::<u32>::from_bytes(encoded).unwrap(), 1..0x80);
+
+        let err = Decoder::new(encoded)
+            .with_strict()
+            .decode::<Range<u32>>()
+            .unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::NonCanonicalInteger);
+
+        // the leading zero of 0x80 is required
+        let encoded: &[u8] = &[0x30, 7, 0x02, 1, 1, 0x02, 2, 0, 0x80];
+        let mut decoder = Decoder::new(encoded).with_strict();
+        assert_eq!(decoder.decode::<Range<u32>>().unwrap(), 1..0x80);
+    }
+
+    #[test]
+    fn non_minimal_nested() {
+        let tag = Tag::context(0).constructed();
+        let encoded: &[u8] = &[0xA0, 10, 0x30, 8, 0x02, 2, 0, 1, 0x02, 2, 0, 0x80];
+
+        let mut decoder = Decoder::new(encoded);
+        let range: Range<u32> = decoder.decode_tagged_value(tag).unwrap();
+        assert_eq!(range, 1..0x80);
+
+        let mut decoder = Decoder::new(encoded).with_strict();
+        let err = decoder
+            .decode_tagged_value::<_, Range<u32>>(tag)
+            .unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::NonCanonicalInteger);
+
+        let tagged = Decoder::new(encoded)
+            .with_strict()
+            .decode::<TaggedSlice>()
+            .unwrap();
+        let err = tagged.value_decoder().decode::<Range<u32>>().unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::NonCanonicalInteger);
+    }
+}
@@ -1,18 +1,30 @@
 use crate::{
     Decodable, Decoder, Encodable, Encoder, Error, ErrorKind, Length, Result, Tag, TagLike,
 };
-use core::convert::TryFrom;
+use core::{convert::TryFrom, fmt};
 
 /// These are tags like in SIMPLE-TLV.
 ///
 /// The tag field consists of a single byte encoding a tag number from 1 to 254. The values '00' and 'FF' are invalid.
 ///
-/// The use case is that PIV (FIPS 201) data objects generally use BER-TLV, but, for historical reasons,
-/// label entries with "simple" tags (in particular, tag numbers larger than 30 are still encoded
-/// as single bytes.
-#[derive(Clone, Copy, Eq, PartialEq)]
+/// A `SimpleTag` implies SIMPLE-TLV framing: data objects tagged with it, e.g.
+/// [`TaggedSlice<SimpleTag>`](crate::TaggedSlice) or `#[tlv(simple = "..")]` fields, code their
+/// lengths as SIMPLE-TLV does, with `0xFF` followed by two bytes from 255 bytes on.
+///
+/// PIV (FIPS 201) data objects, on the other hand, label entries with single byte tags (in
+/// particular, tag numbers larger than 30 are still encoded as single bytes), but code their
+/// lengths as BER-TLV does. Use [`Tag`] for these, e.g. `Tag::try_from(0x30)`.
+#[derive(Clone, Copy, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub struct SimpleTag(u8);
 
+impl SimpleTag {
+    /// The tag number, i.e. the single byte encoding this tag.
+    pub fn value(&self) -> u8 {
+        self.0
+    }
+}
+
 impl TryFrom<u8> for SimpleTag {
     type Error = Error;
     fn try_from(tag_number: u8) -> Result<Self> {
@@ -23,6 +35,23 @@ impl TryFrom<u8> for SimpleTag {
     }
 }
 
+/// Reads the first byte, like `Tag`'s implementation reads the leading tag bytes.
+///
+/// Fails with [`ErrorKind::Truncated`] on an empty slice.
+impl TryFrom<&'_ [u8]> for SimpleTag {
+    type Error = Error;
+    fn try_from(encoding: &[u8]) -> Result<Self> {
+        let mut decoder = Decoder::new(encoding);
+        decoder.decode()
+    }
+}
+
+impl fmt::Debug for SimpleTag {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "SimpleTag(0x{:02x})", self.0)
+    }
+}
+
 impl TagLike for SimpleTag {
     fn embedding(self) -> Tag {
         use crate::Class::*;
@@ -32,6 +61,18 @@ impl TagLike for SimpleTag {
             number: self.0 as u16,
         }
     }
+
+    fn length_encoded_length(length: Length) -> Result<Length> {
+        Ok(length.simple_encoded_length())
+    }
+
+    fn encode_length(length: Length, encoder: &mut Encoder<'_>) -> Result<()> {
+        length.encode_simple(encoder)
+    }
+
+    fn decode_length(decoder: &mut Decoder<'_>) -> Result<Length> {
+        Length::decode_simple(decoder)
+    }
 }
 
 impl Decodable<'_> for SimpleTag {
@@ -52,7 +93,7 @@ impl Encodable for SimpleTag {
 
 #[cfg(test)]
 mod tests {
-    use crate::{Encodable, SimpleTag, TaggedSlice};
+    use crate::{Encodable, ErrorKind, SimpleTag, TaggedSlice};
     use core::convert::TryFrom;
 
     #[test]
@@ -71,7 +112,38 @@ mod tests {
         let slice = &[43u8; 256];
         let long = TaggedSlice::from(tag, slice).unwrap();
         let encoded = long.encode_to_slice(&mut buf).unwrap();
-        assert_eq!(&encoded[..4], &[37, 0x82, 0x01, 0x00]);
+        assert_eq!(&encoded[..4], &[37, 0xFF, 0x01, 0x00]);
         assert_eq!(&encoded[4..], slice);
     }
+
+    #[test]
+    fn try_from_slice() {
+        assert_eq!(
+            SimpleTag::try_from(&[0x5C, 0x03][..]).unwrap(),
+            SimpleTag::try_from(0x5C).unwrap()
+        );
+        assert_eq!(
+            SimpleTag::try_from(&[][..]).unwrap_err().kind(),
+            ErrorKind::Truncated
+        );
+        for byte in [0x00, 0xFF] {
+            assert_eq!(
+                SimpleTag::try_from(&[byte][..]).unwrap_err().kind(),
+                ErrorKind::InvalidTag { byte }
+            );
+        }
+    }
+
+    #[test]
+    fn value_and_ordering() {
+        let low = SimpleTag::try_from(0x01).unwrap();
+        let high = SimpleTag::try_from(0xFE).unwrap();
+        assert_eq!(low.value(), 0x01);
+        assert_eq!(high.value(), 0xFE);
+        assert!(low < high);
+
+        let mut tags = [high, low];
+        tags.sort();
+        assert_eq!(tags, [low, high]);
+    }
 }
@@ -0,0 +1,143 @@
+//! Documents framed entirely as SIMPLE-TLV.
+
+use crate::{Decodable, Decoder, Encodable, Encoder, Length, Result, SimpleTag, TaggedSlice};
+
+/// SIMPLE-TLV decoder, for documents where every data object has a simple tag.
+///
+/// As for [`TaggedSlice<SimpleTag>`], lengths follow the SIMPLE-TLV rules of ISO 7816-4:
+/// one byte up to 254, otherwise `0xFF` followed by two bytes.
+/// The content of each data object is opaque, and decoded with a BER-TLV [`Decoder`] if needed.
+#[derive(Debug)]
+pub struct SimpleDecoder<'a> {
+    decoder: Decoder<'a>,
+}
+
+impl<'a> SimpleDecoder<'a> {
+    /// Create a new decoder for the given byte slice.
+    pub fn new(bytes: &'a [u8]) -> Self {
+        Self {
+            decoder: Decoder::new(bytes),
+        }
+    }
+
+    /// Decode the next data object, returning its tag and content.
+    pub fn decode(&mut self) -> Result<(SimpleTag, &'a [u8])> {
+        self.decoder.decode_simple_tlv()
+    }
+
+    /// Decode the next data object, checking its tag and returning its content.
+    pub fn decode_tagged_slice(&mut self, tag: SimpleTag) -> Result<&'a [u8]> {
+        self.decoder.decode_simple_tagged_slice(tag)
+    }
+
+    /// Decode the next data object, checking its tag and decoding its content as a `V`,
+    /// which must consume it entirely.
+    pub fn decode_tagged_value<V: Decodable<'a>>(&mut self, tag: SimpleTag) -> Result<V> {
+        let content = self.decode_tagged_slice(tag)?;
+        TaggedSlice::from(tag, content)?.decode_nested(|decoder| decoder.decode())
+    }
+
+    /// Peek at the next tag without modifying the cursor.
+    pub fn peek_tag(&self) -> Option<SimpleTag> {
+        self.decoder.peek_tag()
+    }
+
+    /// Have we decoded all of the bytes in this decoder?
+    pub fn is_finished(&self) -> bool {
+        self.decoder.is_finished()
+    }
+
+    /// Finish decoding, returning the given value if there is no
+    /// remaining data, or an error otherwise.
+    pub fn finish<T>(self, value: T) -> Result<T> {
+        self.decoder.finish(value)
+    }
+}
+
+/// SIMPLE-TLV encoder, the counterpart of [`SimpleDecoder`].
+#[derive(Debug)]
+pub struct SimpleEncoder<'a> {
+    encoder: Encoder<'a>,
+}
+
+impl<'a> SimpleEncoder<'a> {
+    /// Create a new encoder with the given byte slice as a backing buffer.
+    pub fn new(bytes: &'a mut [u8]) -> Self {
+        Self {
+            encoder: Encoder::new(bytes),
+        }
+    }
+
+    /// Encode a data object with the given tag, whose content is the encoding of `value`.
+    pub fn encode_tagged<E: Encodable + ?Sized>(
+        &mut self,
+        tag: SimpleTag,
+        value: &E,
+    ) -> Result<()> {
+        let length = value.encoded_length()?;
+        self.encoder.encode(&tag)?;
+        length.encode_simple(&mut self.encoder)?;
+        self.encoder.encode(value)
+    }
+
+    /// Number of bytes written to the buffer so far.
+    pub fn finished_len(&self) -> Length {
+        self.encoder.finished_len()
+    }
+
+    /// Finish encoding to the buffer, returning a slice containing the data
+    /// written to the buffer.
+    pub fn finish(self) -> Result<&'a [u8]> {
+        self.encoder.finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{SimpleDecoder, SimpleEncoder};
+    use crate::{ErrorKind, SimpleTag};
+    use core::convert::TryFrom;
+
+    #[test]
+    fn roundtrip() {
+        let first = SimpleTag::try_from(0x01).unwrap();
+        let second = SimpleTag::try_from(0x80).unwrap();
+        let short = [0xAAu8; 0xFE];
+        let long = [0xBBu8; 0x100];
+
+        let mut buf = [0u8; 0x300];
+        let mut encoder = SimpleEncoder::new(&mut buf);
+        encoder.encode_tagged(first, &short[..]).unwrap();
+        encoder.encode_tagged(second, &long[..]).unwrap();
+        let encoded = encoder.finish().unwrap();
+
+        // no `0x81` prefix below 255, unlike BER-TLV
+        assert_eq!(encoded[..2], [0x01, 0xFE]);
+        assert_eq!(encoded[0x100..0x104], [0x80, 0xFF, 0x01, 0x00]);
+
+        let mut decoder = SimpleDecoder::new(encoded);
+        assert_eq!(decoder.peek_tag(), Some(first));
+        assert_eq!(decoder.decode_tagged_slice(first).unwrap(), &short[..]);
+        assert_eq!(decoder.decode().unwrap(), (second, &long[..]));
+        assert!(decoder.finish(()).is_ok());
+    }
+
+    #[test]
+    fn nested_value() {
+        let tag = SimpleTag::try_from(0x5C).unwrap();
+        let mut decoder = SimpleDecoder::new(&[0x5C, 0x03, 0x04, 0x01, 0xAA]);
+        let value: crate::TaggedSlice = decoder.decode_tagged_value(tag).unwrap();
+        assert_eq!(value.as_bytes(), &[0xAA]);
+    }
+
+    #[test]
+    fn truncated() {
+        let mut decoder = SimpleDecoder::new(&[0x01, 0xFF, 0x01]);
+        assert_eq!(decoder.decode().unwrap_err().kind(), ErrorKind::Truncated);
+
+        let mut decoder = SimpleDecoder::new(&[0x01, 0x03, 0xAA]);
+        let err = decoder.decode().unwrap_err();
+        assert!(matches!(err.kind(), ErrorKind::Length { .. }));
+        assert!(decoder.finish(()).is_err());
+    }
+}
@@ -1,14 +1,22 @@
 use crate::{Length, Result};
-use core::convert::TryFrom;
+use core::{
+    convert::TryFrom,
+    hash::{Hash, Hasher},
+};
 
 /// Slice of at most `Length::max()` bytes.
-#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+#[derive(Copy, Clone, Debug)]
 pub struct Slice<'a> {
     /// Inner value
     inner: &'a [u8],
 
     /// Precomputed `Length` (avoids possible panicking conversions)
     length: Length,
+
+    /// Whether the slice was decoded by a strict [`Decoder`](crate::Decoder)
+    ///
+    /// Not part of the value: ignored by comparisons and hashing.
+    strict: bool,
 }
 
 impl<'a> Slice<'a> {
@@ -18,9 +26,20 @@ impl<'a> Slice<'a> {
         Ok(Self {
             inner: slice,
             length: Length::try_from(slice.len())?,
+            strict: false,
         })
     }
 
+    /// Mark the slice as decoded strictly, so decoders of its content are strict as well.
+    pub(crate) fn with_strict(self, strict: bool) -> Self {
+        Self { strict, ..self }
+    }
+
+    /// Was the slice decoded strictly?
+    pub(crate) fn is_strict(self) -> bool {
+        self.strict
+    }
+
     /// Borrow the inner byte slice
     pub fn as_bytes(&self) -> &'a [u8] {
         self.inner
@@ -37,6 +56,20 @@ impl<'a> Slice<'a> {
     }
 }
 
+impl PartialEq for Slice<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        self.inner == other.inner
+    }
+}
+
+impl Eq for Slice<'_> {}
+
+impl Hash for Slice<'_> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.inner.hash(state);
+    }
+}
+
 impl AsRef<[u8]> for Slice<'_> {
     fn as_ref(&self) -> &[u8] {
         self.as_bytes()
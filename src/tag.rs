@@ -15,7 +15,11 @@ const CONSTRUCTED_FLAG: u8 = 1u8 << CONSTRUCTED_OFFSET;
 /// Indicator bit for constructed form encoding (i.e. vs primitive form)
 const NOT_LAST_TAG_OCTET_FLAG: u8 = 1u8 << 7;
 
-#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+/// Maximum number of octets following the first one, enough for 14-bit tag numbers
+const MAX_SUBSEQUENT_TAG_OCTETS: usize = 2;
+
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 #[repr(u8)]
 /// Class of BER tag.
 pub enum Class {
@@ -25,6 +29,14 @@ pub enum Class {
     Private = 0b11,
 }
 
+impl Class {
+    /// All classes, in order of their encoding.
+    pub const fn all() -> [Class; 4] {
+        use Class::*;
+        [Universal, Application, Context, Private]
+    }
+}
+
 impl TryFrom<u8> for Class {
     type Error = Error;
     fn try_from(value: u8) -> Result<Self> {
@@ -39,8 +51,71 @@ impl TryFrom<u8> for Class {
     }
 }
 
+/// Universal tags of well-known ASN.1 types, see [`Tag::universal_kind`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[non_exhaustive]
+pub enum UniversalKind {
+    Boolean,
+    Integer,
+    BitString,
+    OctetString,
+    Null,
+    ObjectIdentifier,
+    Enumerated,
+    Utf8String,
+    Sequence,
+    Set,
+    PrintableString,
+    UtcTime,
+    GeneralizedTime,
+}
+
+impl UniversalKind {
+    /// All kinds, in order of their tag number.
+    pub const fn all() -> [UniversalKind; 13] {
+        use UniversalKind::*;
+        [
+            Boolean,
+            Integer,
+            BitString,
+            OctetString,
+            Null,
+            ObjectIdentifier,
+            Enumerated,
+            Utf8String,
+            Sequence,
+            Set,
+            PrintableString,
+            UtcTime,
+            GeneralizedTime,
+        ]
+    }
+
+    /// The tag of this kind, e.g. [`Tag::SEQUENCE`] for [`UniversalKind::Sequence`].
+    pub const fn tag(self) -> Tag {
+        use UniversalKind::*;
+        match self {
+            Boolean => Tag::BOOLEAN,
+            Integer => Tag::INTEGER,
+            BitString => Tag::BIT_STRING,
+            OctetString => Tag::OCTET_STRING,
+            Null => Tag::NULL,
+            ObjectIdentifier => Tag::OBJECT_IDENTIFIER,
+            Enumerated => Tag::ENUMERATED,
+            Utf8String => Tag::UTF8_STRING,
+            Sequence => Tag::SEQUENCE,
+            Set => Tag::SET,
+            PrintableString => Tag::PRINTABLE_STRING,
+            UtcTime => Tag::UTC_TIME,
+            GeneralizedTime => Tag::GENERALIZED_TIME,
+        }
+    }
+}
+
 /// The tag field consists of a single byte encoding a tag number from 1 to 254. The values '00' and 'FF' are invalid.
-#[derive(Clone, Copy, Eq, PartialEq)]
+#[derive(Clone, Copy, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub struct Tag {
     pub class: Class,
     pub constructed: bool,
@@ -49,11 +124,12 @@ pub struct Tag {
 
 impl Tag {
     pub const BOOLEAN: Self = Self::universal(0x1);
-    pub const INTEGER: Self = Self::universal(0x1);
+    pub const INTEGER: Self = Self::universal(0x2);
     pub const BIT_STRING: Self = Self::universal(0x3);
     pub const OCTET_STRING: Self = Self::universal(0x4);
     pub const NULL: Self = Self::universal(0x5);
     pub const OBJECT_IDENTIFIER: Self = Self::universal(0x6);
+    pub const ENUMERATED: Self = Self::universal(0xA);
     pub const UTF8_STRING: Self = Self::universal(0xC);
     pub const PRINTABLE_STRING: Self = Self::universal(0x13);
     pub const UTC_TIME: Self = Self::universal(0x17);
@@ -61,6 +137,21 @@ impl Tag {
     pub const SEQUENCE: Self = Self::universal(0x10).constructed();
     pub const SET: Self = Self::universal(0x11).constructed();
 
+    /// The well-known ASN.1 type this tag stands for, if any.
+    ///
+    /// The tag must match the associated constant exactly, e.g. a primitive tag number `0x10`
+    /// is not recognized as [`UniversalKind::Sequence`], nor is a constructed OCTET STRING.
+    pub fn universal_kind(&self) -> Option<UniversalKind> {
+        UniversalKind::all()
+            .iter()
+            .copied()
+            .find(|kind| kind.tag() == *self)
+    }
+
+    /// Tag with the given class, constructed bit and number, as is.
+    ///
+    /// Suits values known to be valid, e.g. constants or the tags in code generated by the
+    /// derive; see [`Tag::try_new`] for numbers coming from elsewhere.
     pub fn from(class: Class, constructed: bool, number: u16) -> Self {
         Self {
             class,
@@ -68,6 +159,31 @@ impl Tag {
             number,
         }
     }
+
+    /// Tag with the given class, constructed bit and number, rejecting combinations that
+    /// cannot be encoded or are not in use with [`ErrorKind::InvalidTagNumber`]:
+    ///
+    /// - numbers above `0x3FFF`, which take more than three bytes to encode,
+    /// - universal numbers rejected by [`Tag::try_universal`].
+    ///
+    /// Use this for numbers from configuration or user input, and [`Tag::from`] otherwise.
+    pub fn try_new(class: Class, constructed: bool, number: u16) -> Result<Self> {
+        if class == Class::Universal {
+            return Self::try_universal(number).map(|tag| Self { constructed, ..tag });
+        }
+        if number > 0x3FFF {
+            return Err(ErrorKind::InvalidTagNumber { class, number }.into());
+        }
+        Ok(Self::from(class, constructed, number))
+    }
+
+    /// Highest tag number of the universal class in conventional use,
+    /// i.e. fitting in the single-byte form.
+    pub const MAX_UNIVERSAL_NUMBER: u16 = 0x1E;
+
+    /// Universal tag with the given number.
+    ///
+    /// The number is not checked, see [`Tag::try_universal`] for a checked variant.
     pub const fn universal(number: u16) -> Self {
         Self {
             class: Class::Universal,
@@ -76,6 +192,22 @@ impl Tag {
         }
     }
 
+    /// Universal tag with the given number, rejecting `0` and numbers above
+    /// [`Tag::MAX_UNIVERSAL_NUMBER`] with [`ErrorKind::InvalidTagNumber`].
+    ///
+    /// The number `0` is reserved for the end-of-contents marker of BER, and universal tags
+    /// with larger numbers are not in use, so these are likely typos.
+    pub fn try_universal(number: u16) -> Result<Self> {
+        if number == 0 || number > Self::MAX_UNIVERSAL_NUMBER {
+            return Err(ErrorKind::InvalidTagNumber {
+                class: Class::Universal,
+                number,
+            }
+            .into());
+        }
+        Ok(Self::universal(number))
+    }
+
     pub const fn application(number: u16) -> Self {
         Self {
             class: Class::Application,
@@ -112,6 +244,68 @@ impl Tag {
             number,
         }
     }
+
+    /// Is this a tag of the universal class?
+    pub const fn is_universal(&self) -> bool {
+        matches!(self.class, Class::Universal)
+    }
+
+    /// Is this a tag of the application class?
+    ///
+    /// ```
+    /// use flexiber::Tag;
+    ///
+    /// let tag = Tag::application(0x1E).constructed();
+    /// assert!(tag.is_application());
+    /// assert!(!tag.is_universal() && !tag.is_context() && !tag.is_private());
+    /// ```
+    pub const fn is_application(&self) -> bool {
+        matches!(self.class, Class::Application)
+    }
+
+    /// Is this a tag of the context-specific class?
+    pub const fn is_context(&self) -> bool {
+        matches!(self.class, Class::Context)
+    }
+
+    /// Is this a tag of the private class?
+    pub const fn is_private(&self) -> bool {
+        matches!(self.class, Class::Private)
+    }
+
+    /// Number of bytes of the encoding of this tag.
+    const fn encoded_len(&self) -> u16 {
+        match self.number {
+            0..=0x1E => 1,
+            0x1F..=0x7F => 2,
+            0x80..=0x3FFF => 3,
+            0x4000..=0xFFFF => 4,
+        }
+    }
+
+    /// Total length of a TLV with this tag and `content` bytes of content,
+    /// like [`Length::tlv_length`], but usable in constants, e.g. to size buffers:
+    ///
+    /// ```
+    /// use flexiber::Tag;
+    ///
+    /// const DISCOVERY: Tag = Tag::application(0x1E).constructed();
+    /// let buf = [0u8; DISCOVERY.tlv_len(0x12) as usize];
+    /// assert_eq!(buf.len(), 20);
+    /// ```
+    ///
+    /// Panics, or fails to compile in a constant, if the tag number cannot be encoded
+    /// or the total length exceeds `u16::MAX`.
+    pub const fn tlv_len(&self, content: u16) -> u16 {
+        if self.number > 0x3FFF {
+            panic!("tag number cannot be encoded");
+        }
+        let header = self.encoded_len() + Length(content).encoded_len();
+        match header.checked_add(content) {
+            Some(len) => len,
+            None => panic!("TLV is longer than 65535 bytes"),
+        }
+    }
 }
 
 impl TryFrom<&'_ [u8]> for Tag {
@@ -152,9 +346,33 @@ pub trait TagLike: Copy + PartialEq + Sized {
     }
 
     /// Ergonomic way to get a TaggedValue for a given tag and value
+    ///
+    /// Given a reference, this encodes as `tag { content }` uniformly for raw byte slices and
+    /// [`Encodable`] values, e.g. when building the fields of a [`Container`][crate::Container]:
+    /// `tag.with_value(&bytes[..])` and `tag.with_value(&nested)` both work.
     fn with_value<V>(self, value: V) -> TaggedValue<V, Self> {
         TaggedValue::new(self, value)
     }
+
+    /// Number of bytes encoding the length of a data object with a tag of this type.
+    ///
+    /// Lengths follow the BER-TLV rules, unless the tag type overrides this as [`SimpleTag`]
+    /// does, see [`Length`].
+    ///
+    /// [`SimpleTag`]: crate::SimpleTag
+    fn length_encoded_length(length: Length) -> Result<Length> {
+        length.encoded_length()
+    }
+
+    /// Encode the length of a data object with a tag of this type.
+    fn encode_length(length: Length, encoder: &mut Encoder<'_>) -> Result<()> {
+        length.encode(encoder)
+    }
+
+    /// Decode the length of a data object with a tag of this type.
+    fn decode_length(decoder: &mut Decoder<'_>) -> Result<Length> {
+        Length::decode(decoder)
+    }
 }
 
 impl TagLike for Tag {
@@ -173,9 +391,6 @@ impl fmt::Display for Tag {
 
 impl fmt::Debug for Tag {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let mut buf = [0u8; 3];
-        let mut encoder = Encoder::new(&mut buf);
-        encoder.encode(self).unwrap();
         write!(
             f,
             "Tag(class = {:?}, constructed = {}, number = {})",
@@ -186,12 +401,7 @@ impl fmt::Debug for Tag {
 
 impl Encodable for Tag {
     fn encoded_length(&self) -> Result<Length> {
-        match self.number {
-            0..=0x1E => Ok(Length(1)),
-            0x1F..=0x7F => Ok(Length(2)),
-            0x80..=0x3FFF => Ok(Length(3)),
-            0x4000..=0xFFFF => Ok(Length(4)),
-        }
+        Ok(Length(self.encoded_len()))
     }
 
     fn encode(&self, encoder: &mut Encoder<'_>) -> Result<()> {
@@ -217,6 +427,10 @@ impl Encodable for Tag {
 impl Decodable<'_> for Tag {
     fn decode(decoder: &mut Decoder<'_>) -> Result<Self> {
         let first_byte = decoder.byte()?;
+        // end-of-contents marker of indefinite lengths, which are not supported, or filler
+        if decoder.is_strict() && first_byte == 0 {
+            return Err(ErrorKind::InvalidTag { byte: 0 }.into());
+        }
         let class = (first_byte >> 6).try_into()?;
         let constructed = first_byte & CONSTRUCTED_FLAG != 0;
         // remove class and primitive/constructed bits
@@ -225,18 +439,30 @@ impl Decodable<'_> for Tag {
         let number = match first_byte_masked {
             number @ 0..=0x1E => number as u16,
             _ => {
-                let second_byte = decoder.byte()?;
-                if second_byte & NOT_LAST_TAG_OCTET_FLAG == 0 {
-                    let number = second_byte;
-                    number as u16
-                } else {
-                    let number = second_byte & (!NOT_LAST_TAG_OCTET_FLAG);
-                    let third_byte = decoder.byte()?;
-                    if third_byte & NOT_LAST_TAG_OCTET_FLAG == 0 {
-                        ((number as u16) << 7) | (third_byte as u16)
-                    } else {
-                        // todo()
-                        return Err(Error::from(ErrorKind::InvalidLength));
+                let mut number = 0u16;
+                let mut subsequent_octets = 0;
+                loop {
+                    let byte = decoder.byte()?;
+                    // unreachable with two subsequent octets, but never wrap silently
+                    number = number
+                        .checked_mul(1 << 7)
+                        .map(|number| number | (byte & !NOT_LAST_TAG_OCTET_FLAG) as u16)
+                        .ok_or(ErrorKind::InvalidTag { byte })?;
+                    subsequent_octets += 1;
+                    if byte & NOT_LAST_TAG_OCTET_FLAG == 0 {
+                        // the short form, or fewer subsequent octets, would do
+                        let minimal = match subsequent_octets {
+                            1 => number > 0x1E,
+                            _ => number > 0x7F,
+                        };
+                        if decoder.is_strict() && !minimal {
+                            return Err(ErrorKind::NonCanonicalTag.into());
+                        }
+                        break number;
+                    }
+                    // bound the read, as a corrupt stream may continue indefinitely
+                    if subsequent_octets == MAX_SUBSEQUENT_TAG_OCTETS {
+                        return Err(ErrorKind::InvalidTag { byte }.into());
                     }
                 }
             }
@@ -251,7 +477,91 @@ impl Decodable<'_> for Tag {
 
 #[cfg(test)]
 mod tests {
-    use crate::{Decodable, Encodable, Tag};
+    use crate::{Class, Decodable, Encodable, Tag, UniversalKind};
+    use core::convert::TryFrom;
+
+    #[test]
+    fn try_new() {
+        assert_eq!(
+            Tag::try_new(Class::Universal, true, 0x10).unwrap(),
+            Tag::SEQUENCE
+        );
+        assert_eq!(
+            Tag::try_new(Class::Context, false, 0).unwrap(),
+            Tag::context(0)
+        );
+        assert_eq!(
+            Tag::try_new(Class::Private, false, 0x3FFF).unwrap(),
+            Tag::private(0x3FFF)
+        );
+        for (class, number) in [
+            (Class::Universal, 0),
+            (Class::Universal, 0x1F),
+            (Class::Application, 0x4000),
+            (Class::Context, 0xFFFF),
+        ] {
+            assert_eq!(
+                Tag::try_new(class, false, number).unwrap_err().kind(),
+                crate::ErrorKind::InvalidTagNumber { class, number }
+            );
+        }
+    }
+
+    #[test]
+    fn try_universal() {
+        assert_eq!(Tag::try_universal(0x10).unwrap(), Tag::universal(0x10));
+        assert_eq!(Tag::try_universal(0x1E).unwrap(), Tag::universal(0x1E));
+        for number in [0, 0x1F, 0xAA] {
+            assert_eq!(
+                Tag::try_universal(number).unwrap_err().kind(),
+                crate::ErrorKind::InvalidTagNumber {
+                    class: Class::Universal,
+                    number
+                }
+            );
+        }
+    }
+
+    #[test]
+    fn class_roundtrip() {
+        for (i, class) in Class::all().iter().enumerate() {
+            assert_eq!(*class as u8, i as u8);
+            assert_eq!(Class::try_from(*class as u8).unwrap(), *class);
+        }
+        assert!(Class::try_from(4).is_err());
+    }
+
+    #[test]
+    fn class_predicates() {
+        for class in Class::all() {
+            let tag = Tag::from(class, false, 1);
+            assert_eq!(tag.is_universal(), class == Class::Universal);
+            assert_eq!(tag.is_application(), class == Class::Application);
+            assert_eq!(tag.is_context(), class == Class::Context);
+            assert_eq!(tag.is_private(), class == Class::Private);
+        }
+
+        // usable in constants
+        const _: () = assert!(Tag::SEQUENCE.is_universal());
+    }
+
+    #[test]
+    fn universal_kind() {
+        for kind in UniversalKind::all() {
+            assert_eq!(kind.tag().universal_kind(), Some(kind));
+        }
+
+        let mut buf = [0u8; 1];
+        assert_eq!(Tag::INTEGER.encode_to_slice(&mut buf).unwrap(), &[0x02]);
+        assert_eq!(
+            Tag::from_bytes(&[0x30]).unwrap().universal_kind(),
+            Some(UniversalKind::Sequence)
+        );
+
+        assert_eq!(Tag::universal(0x10).universal_kind(), None);
+        assert_eq!(Tag::OCTET_STRING.constructed().universal_kind(), None);
+        assert_eq!(Tag::context(0x2).universal_kind(), None);
+    }
 
     #[test]
     fn reconstruct() {
@@ -282,4 +592,125 @@ mod tests {
         let tag2 = Tag::from_bytes(encoded).unwrap();
         assert_eq!(tag, tag2);
     }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn debug_unsupported_number() {
+        // not encodable, but still printable
+        let tag = Tag::from(Class::Private, false, 0x4000);
+        assert!(tag.encode_to_slice(&mut [0u8; 4]).is_err());
+        assert_eq!(
+            alloc::format!("{:?}", tag),
+            "Tag(class = Private, constructed = false, number = 16384)"
+        );
+    }
+
+    #[test]
+    fn longest_tags() {
+        use crate::ErrorKind;
+
+        for (encoded, tag) in [
+            (&[0x1F, 0x1F][..], Tag::universal(0x1F)),
+            (&[0xDF, 0x7F], Tag::private(0x7F)),
+            (&[0x5F, 0x81, 0x00], Tag::application(0x80)),
+            (&[0xFF, 0xFF, 0x7F], Tag::private(0x3FFF).constructed()),
+        ] {
+            assert_eq!(Tag::from_bytes(encoded).unwrap(), tag);
+            let mut buf = [0u8; 3];
+            assert_eq!(tag.encode_to_slice(&mut buf).unwrap(), encoded);
+        }
+
+        // one more octet would overflow the tag number
+        assert_eq!(
+            Tag::from_bytes(&[0xFF, 0xFF, 0xFF, 0x7F])
+                .unwrap_err()
+                .kind(),
+            ErrorKind::InvalidTag { byte: 0xFF }
+        );
+    }
+
+    #[test]
+    fn strict() {
+        use crate::{Decoder, ErrorKind};
+
+        for (encoded, tag) in [
+            (&[0x1F, 0x05][..], Tag::universal(0x05)),
+            (&[0x5F, 0x1E], Tag::application(0x1E)),
+            (&[0x9F, 0x80, 0x7F], Tag::context(0x7F)),
+        ] {
+            assert_eq!(Tag::from_bytes(encoded).unwrap(), tag);
+            let err = Decoder::new(encoded).with_strict().decode::<Tag>();
+            assert_eq!(err.unwrap_err().kind(), ErrorKind::NonCanonicalTag);
+        }
+        for encoded in [&[0x1F, 0x1F][..], &[0x5F, 0x81, 0x00]] {
+            let mut decoder = Decoder::new(encoded).with_strict();
+            assert_eq!(
+                decoder.decode::<Tag>().unwrap(),
+                Tag::from_bytes(encoded).unwrap()
+            );
+        }
+    }
+
+    #[test]
+    fn tlv_len() {
+        const LEN: u16 = Tag::application(0x2082).tlv_len(0x100);
+        assert_eq!(LEN, 3 + 3 + 0x100);
+
+        for (tag, content) in [
+            (Tag::universal(0x10), 0u16),
+            (Tag::application(0x1E).constructed(), 0x7F),
+            (Tag::context(0x1F), 0x80),
+            (Tag::private(0x3FFF), 0xFF00),
+        ] {
+            assert_eq!(
+                tag.tlv_len(content),
+                crate::Length::tlv_length(tag, content.into()).unwrap()
+            );
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn tlv_len_overflow() {
+        Tag::universal(0x10).tlv_len(u16::MAX);
+    }
+
+    #[test]
+    fn bounded_continuation_octets() {
+        use crate::{Decoder, ErrorKind};
+
+        // a run of continuation octets is cut off after the longest supported tag,
+        // whatever its length or the bytes it is made of
+        let mut run = [0x80u8; 64];
+        for (i, byte) in run.iter_mut().enumerate() {
+            *byte |= (i as u8).wrapping_mul(37);
+        }
+        for first in [0x1F, 0x3F, 0x5F, 0xFF] {
+            for len in 2..run.len() {
+                let mut bytes = [0u8; 65];
+                bytes[0] = first;
+                bytes[1..=len].copy_from_slice(&run[..len]);
+                let mut decoder = Decoder::new(&bytes[..=len]);
+                let err = decoder.decode::<Tag>().unwrap_err();
+                assert_eq!(err.kind(), ErrorKind::InvalidTag { byte: run[1] });
+                assert_eq!(err.position(), Some(3u8.into()));
+            }
+        }
+
+        // too short runs are truncated
+        assert_eq!(
+            Tag::from_bytes(&[0x1F, 0x81]).unwrap_err().kind(),
+            ErrorKind::Truncated
+        );
+    }
+
+    #[test]
+    fn zero_first_byte() {
+        use crate::{Decoder, ErrorKind};
+
+        // the end-of-contents marker, which is no tag when decoding strictly
+        assert_eq!(Tag::from_bytes(&[0x00]).unwrap(), Tag::universal(0));
+        let err = Decoder::new(&[0x00]).with_strict().decode::<Tag>();
+        assert_eq!(err.unwrap_err().kind(), ErrorKind::InvalidTag { byte: 0 });
+    }
 }
@@ -7,10 +7,10 @@ use crate::{
 };
 
 /// BER-TLV data object.
-#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
 pub struct TaggedValue<V, T = Tag> {
-    tag: T,
-    value: V,
+    pub(crate) tag: T,
+    pub(crate) value: V,
 }
 
 /// Raw BER-TLV data object `TaggedValue<Slice<'_>>`.
@@ -27,12 +27,21 @@ where
     pub fn tag(&self) -> T {
         self.tag
     }
+
+    /// Transform the value, keeping the tag, e.g. to parse a decoded slice
+    /// while keeping its tag for re-encoding.
+    pub fn map<U>(self, f: impl FnOnce(V) -> U) -> TaggedValue<U, T> {
+        TaggedValue {
+            tag: self.tag,
+            value: f(self.value),
+        }
+    }
 }
 
 impl<E, T> TaggedValue<&'_ E, T>
 where
-    E: Encodable,
-    T: Copy + Encodable,
+    E: Encodable + ?Sized,
+    T: Encodable + TagLike,
 {
     fn header(&self) -> Result<Header<T>> {
         Ok(Header {
@@ -44,8 +53,8 @@ where
 
 impl<E, T> Encodable for TaggedValue<&E, T>
 where
-    E: Encodable,
-    T: Copy + Encodable,
+    E: Encodable + ?Sized,
+    T: Encodable + TagLike,
 {
     fn encoded_length(&self) -> Result<Length> {
         self.header()?.encoded_length()? + self.value.encoded_length()?
@@ -61,10 +70,18 @@ where
     T: Copy,
 {
     /// Create a new tagged slice, checking lengths.
+    ///
+    /// Fails with [`ErrorKind::Overlength`] if the slice is longer than [`Length::max()`].
     pub fn from(tag: T, slice: &'a [u8]) -> Result<Self> {
         Slice::new(slice)
             .map(|slice| Self { tag, value: slice })
-            .map_err(|_| (ErrorKind::InvalidLength).into())
+            .map_err(|_| {
+                ErrorKind::Overlength {
+                    needed: slice.len(),
+                    available: Length::max(),
+                }
+                .into()
+            })
     }
 
     /// Borrow the inner byte slice.
@@ -91,16 +108,69 @@ where
         })
     }
 
+    /// Create a new [`Decoder`] for the data contained in the sequence's body.
+    ///
+    /// Unlike [`TaggedSlice::decode_nested`], the caller is responsible for checking
+    /// that the content is fully consumed, e.g. by calling [`Decoder::finish`].
+    ///
+    /// The decoder is strict if this value was decoded by a strict decoder,
+    /// see [`Decoder::with_strict`].
+    pub fn value_decoder(&self) -> Decoder<'a> {
+        let decoder = Decoder::new(self.as_bytes());
+        if self.value.is_strict() {
+            decoder.with_strict()
+        } else {
+            decoder
+        }
+    }
+
     /// Decode nested values, creating a new [`Decoder`] for
     /// the data contained in the sequence's body and passing it to the provided
     /// [`FnOnce`].
+    ///
+    /// With the `tag-path` feature, errors record the tag of this value, see `Error::tag_path`.
+    /// An [`ErrorKind::InvalidValue`] of the content records it in any case.
     pub fn decode_nested<F, R>(&self, f: F) -> Result<R>
     where
         F: FnOnce(&mut Decoder<'a>) -> Result<R>,
+        T: TagLike,
     {
-        let mut nested_decoder = Decoder::new(self.as_bytes());
-        let result = f(&mut nested_decoder)?;
-        nested_decoder.finish(result)
+        let mut nested_decoder = self.value_decoder();
+        f(&mut nested_decoder)
+            .and_then(|result| nested_decoder.finish(result))
+            .map_err(|e| {
+                let tag = self.tag().embedding();
+                e.of_value(tag).in_tag(tag)
+            })
+    }
+}
+
+impl<'a, T> TaggedSlice<'a, T>
+where
+    T: Decodable<'a> + TagLike,
+{
+    /// Check that the content of this constructed value consists exactly of child TLVs,
+    /// without decoding them.
+    ///
+    /// Decoding a constructed value only notices a length inconsistent with its children
+    /// once the nested decoder finishes, or not at all for skipped or optional children.
+    /// This fails with [`ErrorKind::NestedTrailingData`], reporting the tag of this value,
+    /// if the last child TLV is truncated or followed by bytes not forming a TLV.
+    pub fn validate_nested(&self) -> Result<()> {
+        let mut decoder = self.value_decoder();
+        let mut decoded = Length::zero();
+        while !decoder.is_finished() {
+            if decoder.decode::<TaggedSlice<'a, T>>().is_err() {
+                return Err(ErrorKind::NestedTrailingData {
+                    tag: self.tag().embedding(),
+                    decoded,
+                    remaining: (self.length() - decoded)?,
+                }
+                .into());
+            }
+            decoded = (self.length() - decoder.remaining_len()?)?;
+        }
+        Ok(())
     }
 }
 
@@ -111,17 +181,21 @@ where
     fn decode(decoder: &mut Decoder<'a>) -> Result<Self> {
         let header = Header::<T>::decode(decoder)?;
         let tag = header.tag;
-        let len = header.length.to_usize();
+        let len = header.length;
+        let available = decoder.remaining_len()?;
         let value = decoder.bytes(len).map_err(|_| ErrorKind::Length {
             tag: tag.embedding(),
+            expected: Some(len),
+            actual: available,
         })?;
-        Self::from(tag, value)
+        let strict = decoder.is_strict();
+        Self::from(tag, value).map(|tagged| tagged.map(|slice| slice.with_strict(strict)))
     }
 }
 
 impl<T> Encodable for TaggedSlice<'_, T>
 where
-    T: Copy + Encodable,
+    T: Encodable + TagLike,
 {
     fn encoded_length(&self) -> Result<Length> {
         self.header()?.encoded_length()? + self.length()
@@ -152,9 +226,149 @@ where
 
 #[cfg(test)]
 mod tests {
-    use crate::{Encodable, Tag, TaggedSlice};
+    #[cfg(feature = "tag-path")]
+    use crate::Error;
+    use crate::{Decodable, Encodable, Encoder, ErrorKind, Length, Tag, TagLike, TaggedSlice};
     use core::convert::TryFrom;
 
+    #[test]
+    fn from_overlong_slice() {
+        let slice = &[0u8; 0x1_0000];
+        assert!(TaggedSlice::from(Tag::OCTET_STRING, &slice[..0xFFFF]).is_ok());
+
+        let err = TaggedSlice::from(Tag::OCTET_STRING, slice).unwrap_err();
+        assert_eq!(
+            err.kind(),
+            ErrorKind::Overlength {
+                needed: 0x1_0000,
+                available: 0xFFFF,
+            }
+        );
+    }
+
+    #[test]
+    fn uniform_fields() {
+        let mut buf = [0u8; 16];
+        let bytes: &[u8] = &[1, 2];
+
+        // raw slice, `Encodable` array and nested TLV
+        let slice = Tag::OCTET_STRING.with_value(bytes);
+        let array = Tag::OCTET_STRING.with_value(&[1u8, 2]);
+        let nested = TaggedSlice::from(Tag::NULL, &[]).unwrap();
+        let wrapped = Tag::SEQUENCE.with_value(&nested);
+
+        let fields: [&dyn Encodable; 3] = [&slice, &array, &wrapped];
+        let mut encoder = Encoder::new(&mut buf);
+        encoder.encode_untagged_collection(&fields).unwrap();
+        assert_eq!(
+            encoder.finish().unwrap(),
+            &[0x04, 2, 1, 2, 0x04, 2, 1, 2, 0x30, 2, 0x05, 0]
+        );
+    }
+
+    #[test]
+    fn value_decoder() {
+        let tagged = TaggedSlice::from(Tag::SEQUENCE, &[0x04, 1, 0xAA, 0x04, 1, 0xBB]).unwrap();
+        let mut decoder = tagged.value_decoder();
+
+        let first: TaggedSlice = decoder.decode().unwrap();
+        assert_eq!(first.as_bytes(), &[0xAA]);
+        assert!(!decoder.is_finished());
+
+        let second: TaggedSlice = decoder.decode().unwrap();
+        assert_eq!(second.as_bytes(), &[0xBB]);
+        assert!(decoder.finish(()).is_ok());
+    }
+
+    #[test]
+    fn validate_nested() {
+        let tagged = TaggedSlice::from(Tag::SEQUENCE, &[0x04, 1, 0xAA, 0x05, 0]).unwrap();
+        assert!(tagged.validate_nested().is_ok());
+        assert!(TaggedSlice::from(Tag::SEQUENCE, &[])
+            .unwrap()
+            .validate_nested()
+            .is_ok());
+
+        // second child truncated, or a lone byte after the first one
+        for content in [&[0x04, 1, 0xAA, 0x04, 2, 0xBB][..], &[0x04, 1, 0xAA, 0x00]] {
+            let tagged = TaggedSlice::from(Tag::SEQUENCE, content).unwrap();
+            assert_eq!(
+                tagged.validate_nested().unwrap_err().kind(),
+                ErrorKind::NestedTrailingData {
+                    tag: Tag::SEQUENCE,
+                    decoded: Length::from(3u8),
+                    remaining: Length::from(content.len() as u8 - 3),
+                }
+            );
+        }
+    }
+
+    #[cfg(feature = "tag-path")]
+    #[test]
+    fn tag_path() {
+        let app = Tag::application(1).constructed();
+        let outer = TaggedSlice::from(Tag::SEQUENCE, &[0x61, 3, 0x04, 5, 1]).unwrap();
+        let err = outer
+            .decode_nested(|decoder| {
+                let inner: TaggedSlice = decoder.decode()?;
+                inner.decode_nested(|decoder| decoder.decode_tagged_slice(Tag::OCTET_STRING))
+            })
+            .unwrap_err();
+        assert_eq!(err.tag_path(), &[Tag::SEQUENCE, app]);
+
+        let err = Error::from(ErrorKind::Truncated);
+        assert!(err.tag_path().is_empty());
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn hash() {
+        use std::collections::HashSet;
+
+        // the same object under two tags, and repeated
+        let encoded: &[u8] = &[0x04, 1, 0xAA, 0x84, 1, 0xAA, 0x04, 1, 0xAA];
+        let mut decoder = crate::Decoder::new(encoded);
+        let mut objects = HashSet::new();
+        while !decoder.is_finished() {
+            objects.insert(decoder.decode::<TaggedSlice<'_>>().unwrap());
+        }
+        assert_eq!(objects.len(), 2);
+        assert!(objects.contains(&TaggedSlice::from(Tag::context(4), &[0xAA]).unwrap()));
+    }
+
+    #[test]
+    fn map() {
+        let encoded: &[u8] = &[0x5F, 0x2F, 2, 0x40, 0x10];
+        let tagged = TaggedSlice::<Tag>::from_bytes(encoded).unwrap();
+
+        let length = tagged.map(|slice| slice.length());
+        assert_eq!(length.tag(), Tag::application(0x2F));
+        assert_eq!(length.value, 2usize);
+
+        let bytes = tagged.map(|slice| slice.as_bytes());
+        let mut buf = [0u8; 8];
+        assert_eq!(bytes.encode_to_slice(&mut buf).unwrap(), encoded);
+    }
+
+    #[test]
+    fn decode_truncated_value() {
+        let err = TaggedSlice::<Tag>::from_bytes(&[0x04, 0x05, 1, 2]).unwrap_err();
+        assert_eq!(
+            err.kind(),
+            ErrorKind::Length {
+                tag: Tag::OCTET_STRING,
+                expected: Some(Length::from(5u8)),
+                actual: Length::from(2u8),
+            }
+        );
+    }
+
+    #[test]
+    fn decode_overlong_length() {
+        let err = TaggedSlice::<Tag>::from_bytes(&[0x04, 0x83, 0x01, 0x00, 0x00, 1, 2]);
+        assert_eq!(err.unwrap_err().kind(), ErrorKind::LengthTooLong);
+    }
+
     #[test]
     fn encode() {
         let mut buf = [0u8; 1024];
@@ -1,15 +1,15 @@
 // pub use der::{Decodable, Encodable};
 //! Trait definitions
 
-use crate::{header::Header, Decoder, Encoder, Error, Length, Result, Tag, TaggedSlice};
-use core::convert::{TryFrom, TryInto};
+use crate::{Decoder, Encoder, Error, ErrorKind, Length, Result, Tag, TaggedSlice};
+use core::{
+    convert::{TryFrom, TryInto},
+    num::{NonZeroU16, NonZeroU32, NonZeroU64, NonZeroU8, Wrapping},
+};
 
 #[cfg(feature = "alloc")]
 use {alloc::vec::Vec, core::iter};
 
-#[cfg(any(feature = "heapless", feature = "alloc"))]
-use crate::ErrorKind;
-
 /// Decoding trait.
 ///
 /// Decode out of decoder, which essentially is a slice of bytes.
@@ -25,6 +25,16 @@ pub trait Decodable<'a>: Sized {
         let result = Self::decode(&mut decoder)?;
         decoder.finish(result)
     }
+
+    /// Parse `Self` from the start of the provided byte slice, returning it
+    /// together with the remaining, undecoded bytes.
+    ///
+    /// Unlike [`Decodable::from_bytes`], trailing data is not an error.
+    fn from_bytes_partial(bytes: &'a [u8]) -> Result<(Self, &'a [u8])> {
+        let mut decoder = Decoder::new(bytes);
+        let result = Self::decode(&mut decoder)?;
+        Ok((result, decoder.remaining()?))
+    }
 }
 
 impl<'a, X> Decodable<'a> for X
@@ -41,11 +51,14 @@ where
 /// This implementation is quite gotcha-y, since only one byte is peeked.
 ///
 /// It should evaluate to the desired tag via `Tag::try_from(byte)?`.
+///
+/// Padding is skipped first if enabled with [`Decoder::with_padding`].
 impl<'a, T> Decodable<'a> for Option<T>
 where
     T: Decodable<'a> + Tagged,
 {
     fn decode(decoder: &mut Decoder<'a>) -> Result<Option<T>> {
+        decoder.skip_padding()?;
         if let Some(byte) = decoder.peek() {
             debug_now!(
                 "comparing {} against {} interpreted as {}",
@@ -61,6 +74,17 @@ where
     }
 }
 
+/// `Option<Option<T>>` collapses to a single presence check and decodes exactly like
+/// `Option<T>`: it is never `Some(None)`.
+impl<'a, T> Decodable<'a> for Option<Option<T>>
+where
+    T: Decodable<'a> + Tagged,
+{
+    fn decode(decoder: &mut Decoder<'a>) -> Result<Option<Option<T>>> {
+        Option::<T>::decode(decoder).map(|value| value.map(Some))
+    }
+}
+
 /// Encoding trait.
 ///
 /// Encode into encoder, which essentially is a mutable slice of bytes.
@@ -85,27 +109,25 @@ pub trait Encodable {
     /// byte vector.
     #[cfg(feature = "alloc")]
     #[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+    ///
+    /// On error, `buf` is left as it was. If `encode` writes fewer bytes than `encoded_length`
+    /// claims, this fails with [`ErrorKind::Underlength`], and panics in debug builds, naming
+    /// the type with the buggy implementation.
     fn encode_to_vec(&self, buf: &mut Vec<u8>) -> Result<Length> {
         let expected_len = self.encoded_length()?.to_usize();
         let current_len = buf.len();
         buf.reserve(expected_len);
         buf.extend(iter::repeat(0).take(expected_len));
 
-        // TODO(nickray): seems the original in `der` is incorrect here?
-        // let mut encoder = Encoder::new(buf);
         let mut encoder = Encoder::new(&mut buf[current_len..]);
-        self.encode(&mut encoder)?;
-        let actual_len = encoder.finish()?.len();
-
-        if expected_len != actual_len {
-            return Err(ErrorKind::Underlength {
-                expected: expected_len.try_into()?,
-                actual: actual_len.try_into()?,
-            }
-            .into());
+        let result = self
+            .encode(&mut encoder)
+            .and_then(|()| encoder.finish().map(|encoded| encoded.len()))
+            .and_then(|actual_len| check_encoded_length::<Self>(expected_len, actual_len));
+        if result.is_err() {
+            buf.truncate(current_len);
         }
-
-        actual_len.try_into()
+        result
     }
 
     /// Serialize this message as a byte vector.
@@ -126,29 +148,31 @@ pub trait Encodable {
 pub trait EncodableHeapless: Encodable {
     /// Encode this message as BER-TLV, appending it to the provided
     /// heapless byte vector.
+    ///
+    /// Errors are handled as in [`Encodable::encode_to_vec`].
     fn encode_to_heapless_vec<const N: usize>(
         &self,
         buf: &mut heapless::Vec<u8, N>,
     ) -> Result<Length> {
         let expected_len = self.encoded_length()?.to_usize();
         let current_len = buf.len();
-        // TODO(nickray): add a specific error for "Overcapacity" conditional on heapless feature?
         buf.resize_default(current_len + expected_len)
-            .map_err(|_| Error::from(ErrorKind::Overlength))?;
+            .map_err(|_| {
+                Error::from(ErrorKind::BufferTooSmall {
+                    needed: current_len + expected_len,
+                    available: N,
+                })
+            })?;
 
         let mut encoder = Encoder::new(&mut buf[current_len..]);
-        self.encode(&mut encoder)?;
-        let actual_len = encoder.finish()?.len();
-
-        if expected_len != actual_len {
-            return Err(ErrorKind::Underlength {
-                expected: expected_len.try_into()?,
-                actual: actual_len.try_into()?,
-            }
-            .into());
+        let result = self
+            .encode(&mut encoder)
+            .and_then(|()| encoder.finish().map(|encoded| encoded.len()))
+            .and_then(|actual_len| check_encoded_length::<Self>(expected_len, actual_len));
+        if result.is_err() {
+            buf.truncate(current_len);
         }
-
-        actual_len.try_into()
+        result
     }
 
     /// Serialize this message as a byte vector.
@@ -162,6 +186,28 @@ pub trait EncodableHeapless: Encodable {
 #[cfg(feature = "heapless")]
 impl<X> EncodableHeapless for X where X: Encodable {}
 
+/// Check that `encode` wrote as many bytes as `encoded_length` of `T` claimed.
+///
+/// A mismatch is a bug in the implementation for `T`, so debug builds panic naming it.
+#[cfg(any(feature = "alloc", feature = "heapless"))]
+fn check_encoded_length<T: ?Sized>(expected_len: usize, actual_len: usize) -> Result<Length> {
+    debug_assert_eq!(
+        expected_len,
+        actual_len,
+        "`encoded_length` of `{}` does not match the bytes written by `encode`",
+        core::any::type_name::<T>()
+    );
+    if expected_len != actual_len {
+        return Err(ErrorKind::Underlength {
+            expected: expected_len.try_into()?,
+            actual: actual_len.try_into()?,
+        }
+        .into());
+    }
+
+    actual_len.try_into()
+}
+
 // /// Types with an associated BER-TLV [`Tag`].
 // pub trait Tagged {
 //     /// BER-TLV tag
@@ -176,6 +222,13 @@ pub trait Tagged {
     fn tag() -> Tag;
 }
 
+/// Borrowed values have the tag of the values themselves.
+impl<T: Tagged> Tagged for &T {
+    fn tag() -> Tag {
+        T::tag()
+    }
+}
+
 /// Multiple encodables in a container.
 ///
 /// A container implementing `Tagged` has a blanked implementation of `Encodable`.
@@ -189,6 +242,22 @@ pub trait Container {
     fn fields<F, T>(&self, f: F) -> Result<T>
     where
         F: FnOnce(&[&dyn Encodable]) -> Result<T>;
+
+    /// Number of fields of this message.
+    ///
+    /// Fallible, since obtaining the fields may require constructing temporary values.
+    fn field_count(&self) -> Result<usize> {
+        self.fields(|fields| Ok(fields.len()))
+    }
+
+    /// Call the provided function on each field of this message, in order,
+    /// stopping at the first error.
+    ///
+    /// Unlike [`Container::fields`], the callback can be reused, e.g. to visit the
+    /// fields of several containers in turn and encode them as one collection.
+    fn for_each_field(&self, f: &mut dyn FnMut(&dyn Encodable) -> Result<()>) -> Result<()> {
+        self.fields(|fields| fields.iter().try_for_each(|field| f(*field)))
+    }
 }
 
 impl<TaggedContainer> Encodable for TaggedContainer
@@ -196,15 +265,7 @@ where
     TaggedContainer: Tagged + Container,
 {
     fn encoded_length(&self) -> Result<Length> {
-        #[allow(clippy::redundant_closure)]
-        // if we do as clippy tells, we get:
-        // 183 |         let value_length = self.fields(Length::try_from)?;
-        //     |                                 ^^^^^^ one type is more general than the other
-        //     |
-        //     = note: expected type `FnOnce<(&[&dyn Encodable],)>`
-        //                found type `FnOnce<(&[&dyn Encodable],)>`
-        let value_length = self.fields(|encodables| Length::try_from(encodables))?;
-        Header::new(Self::tag(), value_length)?.encoded_length() + value_length
+        self.fields(|encodables| crate::nested::encoded_length(Self::tag(), encodables))
     }
 
     fn encode(&self, encoder: &mut Encoder<'_>) -> Result<()> {
@@ -257,17 +318,119 @@ where
 //     }
 // }
 
+/// Length of a byte slice as raw content.
+///
+/// Fails with [`ErrorKind::Overlength`] if it exceeds [`Length::max()`], as [`TaggedSlice::from`] does.
+fn slice_length(slice: &[u8]) -> Result<Length> {
+    slice.len().try_into().map_err(|_| {
+        ErrorKind::Overlength {
+            needed: slice.len(),
+            available: Length::max(),
+        }
+        .into()
+    })
+}
+
+impl Encodable for [u8] {
+    fn encoded_length(&self) -> Result<Length> {
+        slice_length(self)
+    }
+
+    /// Encode this value as BER-TLV using the provided [`Encoder`].
+    fn encode(&self, encoder: &mut Encoder<'_>) -> Result<()> {
+        slice_length(self)?;
+        encoder.bytes(self)
+    }
+}
+
 impl Encodable for &[u8] {
     fn encoded_length(&self) -> Result<Length> {
-        self.len().try_into()
+        slice_length(self)
     }
 
     /// Encode this value as BER-TLV using the provided [`Encoder`].
     fn encode(&self, encoder: &mut Encoder<'_>) -> Result<()> {
+        slice_length(self)?;
         encoder.bytes(self)
     }
 }
 
+/// Encodes like `[u8]`: a capacity `N` beyond [`Length::max`] is allowed, but encoding
+/// more bytes fails with [`ErrorKind::Overlength`].
+#[cfg(feature = "heapless")]
+#[cfg_attr(docsrs, doc(cfg(feature = "heapless")))]
+impl<const N: usize> Encodable for heapless::Vec<u8, N> {
+    fn encoded_length(&self) -> Result<Length> {
+        (**self).encoded_length()
+    }
+
+    /// Encode this value as BER-TLV using the provided [`Encoder`].
+    fn encode(&self, encoder: &mut Encoder<'_>) -> Result<()> {
+        (**self).encode(encoder)
+    }
+}
+
+/// Encodes like `str`, with the same limit as `heapless::Vec<u8, N>`.
+#[cfg(feature = "heapless")]
+#[cfg_attr(docsrs, doc(cfg(feature = "heapless")))]
+impl<const N: usize> Encodable for heapless::String<N> {
+    fn encoded_length(&self) -> Result<Length> {
+        self.as_str().encoded_length()
+    }
+
+    /// Encode this value as BER-TLV using the provided [`Encoder`].
+    fn encode(&self, encoder: &mut Encoder<'_>) -> Result<()> {
+        self.as_str().encode(encoder)
+    }
+}
+
+/// Encodes like `&[u8]`, e.g. for a scratch region of a buffer that was written beforehand.
+impl Encodable for &mut [u8] {
+    fn encoded_length(&self) -> Result<Length> {
+        (**self).encoded_length()
+    }
+
+    /// Encode this value as BER-TLV using the provided [`Encoder`].
+    fn encode(&self, encoder: &mut Encoder<'_>) -> Result<()> {
+        (**self).encode(encoder)
+    }
+}
+
+impl Encodable for str {
+    fn encoded_length(&self) -> Result<Length> {
+        self.as_bytes().encoded_length()
+    }
+
+    /// Encode this value as BER-TLV using the provided [`Encoder`].
+    fn encode(&self, encoder: &mut Encoder<'_>) -> Result<()> {
+        self.as_bytes().encode(encoder)
+    }
+}
+
+impl Encodable for &str {
+    fn encoded_length(&self) -> Result<Length> {
+        self.as_bytes().encoded_length()
+    }
+
+    /// Encode this value as BER-TLV using the provided [`Encoder`].
+    fn encode(&self, encoder: &mut Encoder<'_>) -> Result<()> {
+        self.as_bytes().encode(encoder)
+    }
+}
+
+/// Trait objects are already encodable through `dyn Encodable`, e.g. with
+/// [`Encodable::encode_to_slice`]; this lets a borrowed one be used where a sized
+/// `Encodable` is expected, e.g. in an `Option<&dyn Encodable>`.
+impl Encodable for &dyn Encodable {
+    fn encoded_length(&self) -> Result<Length> {
+        (**self).encoded_length()
+    }
+
+    fn encode(&self, encoder: &mut Encoder<'_>) -> Result<()> {
+        (**self).encode(encoder)
+    }
+}
+
 // impl Encodable for Option<&[u8]> {
 //     fn encoded_length(&self) -> Result<Length> {
 //         match self {
@@ -285,6 +448,14 @@ impl Encodable for &[u8] {
 //     }
 // }
 
+/// Absent values are not encoded at all.
+///
+/// In particular, `Some(None)` of an `Option<Option<T>>` encodes like `None`.
+///
+/// There is no blanket `Encodable` for `&T`, as it would overlap with the one for
+/// [`Tagged`] + [`Container`] types, so an `Option<&T>` of a borrowed field
+/// is encoded after tagging it, as `Option<TaggedValue<&T, _>>`, or after
+/// turning it into an `Option<&dyn Encodable>`.
 impl<T> Encodable for Option<T>
 where
     T: Encodable,
@@ -323,7 +494,7 @@ macro_rules! impl_array {
                 fn decode(decoder: &mut Decoder<'_>) -> Result<Self> {
                     use core::convert::TryInto;
                     let bytes: &[u8] = decoder.bytes($N as u8)?;
-                    Ok(bytes.try_into().unwrap())
+                    bytes.try_into().or_else(|_| decoder.error(ErrorKind::Truncated))
                 }
             }
         )*
@@ -335,26 +506,206 @@ impl_array!(
     26, 27, 28, 29, 30, 31, 32
 );
 
-#[cfg(test)]
-mod tests {
+/// Borrows exactly `N` content bytes from the decoder, avoiding a copy for large fixed blobs.
+impl<'a, const N: usize> Decodable<'a> for &'a [u8; N] {
+    fn decode(decoder: &mut Decoder<'a>) -> Result<Self> {
+        let bytes: &'a [u8] = decoder.bytes(N)?;
+        bytes
+            .try_into()
+            .or_else(|_| decoder.error(ErrorKind::Truncated))
+    }
+}
 
-    use super::{Container, Tagged};
-    use crate::{Decodable, Encodable, Error, Result, Tag, TagLike, TaggedSlice, TaggedValue};
-    use core::convert::TryFrom;
+/// Borrows all remaining bytes from the decoder, e.g. the entire content
+/// with [`Decoder::decode_tagged_value`].
+impl<'a> Decodable<'a> for &'a [u8] {
+    fn decode(decoder: &mut Decoder<'a>) -> Result<Self> {
+        let len = decoder.remaining_len()?;
+        decoder.bytes(len)
+    }
+}
+
+/// Borrows all remaining bytes from the decoder like `&[u8]`,
+/// failing with [`ErrorKind::InvalidValue`] if they are not UTF-8.
+impl<'a> Decodable<'a> for &'a str {
+    fn decode(decoder: &mut Decoder<'a>) -> Result<Self> {
+        let bytes: &'a [u8] = decoder.decode()?;
+        core::str::from_utf8(bytes)
+            .or_else(|_| decoder.error(ErrorKind::InvalidValue { tag: None }))
+    }
+}
 
-    /// Types that can be tagged.
-    pub(crate) trait Taggable<T: TagLike>: Sized {
-        fn tagged(&self, tag: T) -> TaggedValue<&Self, T> {
-            TaggedValue::new(tag, self)
+/// Flag arrays are packed into `ceil(N / 8)` content bytes, most significant bit first.
+///
+/// The unused trailing bits are zero; decoding fails with [`ErrorKind::InvalidValue`] otherwise.
+impl<const N: usize> Encodable for [bool; N] {
+    fn encoded_length(&self) -> Result<Length> {
+        N.div_ceil(8).try_into()
+    }
+
+    /// Encode this value as BER-TLV using the provided [`Encoder`].
+    fn encode(&self, encoder: &mut Encoder<'_>) -> Result<()> {
+        for chunk in self.chunks(8) {
+            let byte = chunk
+                .iter()
+                .enumerate()
+                .fold(0u8, |byte, (i, &bit)| byte | ((bit as u8) << (7 - i)));
+            encoder.byte(byte)?;
         }
+        Ok(())
     }
+}
 
-    impl<T, X> Taggable<T> for X
-    where
-        X: Sized,
-        T: TagLike,
-    {
+impl<const N: usize> Decodable<'_> for [bool; N] {
+    fn decode(decoder: &mut Decoder<'_>) -> Result<Self> {
+        let bytes = decoder.bytes(N.div_ceil(8))?;
+        let unused_bits = (8 - N % 8) % 8;
+        if let Some(last) = bytes.last() {
+            if last & ((1u16 << unused_bits) - 1) as u8 != 0 {
+                return decoder.error(ErrorKind::InvalidValue { tag: None });
+            }
+        }
+
+        let mut flags = [false; N];
+        for (i, flag) in flags.iter_mut().enumerate() {
+            *flag = bytes[i / 8] & (0x80 >> (i % 8)) != 0;
+        }
+        Ok(flags)
+    }
+}
+
+/// The unit value has empty content, e.g. for ASN.1 NULL with `Tag::NULL.with_value(&())`.
+impl Encodable for () {
+    fn encoded_length(&self) -> Result<Length> {
+        Ok(Length::zero())
+    }
+
+    /// Encode this value as BER-TLV using the provided [`Encoder`].
+    fn encode(&self, _encoder: &mut Encoder<'_>) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// Decodes nothing, so that [`Decoder::decode_tagged_value`] checks the content is empty.
+impl Decodable<'_> for () {
+    fn decode(_decoder: &mut Decoder<'_>) -> Result<Self> {
+        Ok(())
+    }
+}
+
+/// A byte is encoded as a single content byte, like `[u8; 1]`.
+impl Encodable for u8 {
+    fn encoded_length(&self) -> Result<Length> {
+        Ok(Length::from(1u8))
+    }
+
+    /// Encode this value as BER-TLV using the provided [`Encoder`].
+    fn encode(&self, encoder: &mut Encoder<'_>) -> Result<()> {
+        encoder.byte(*self)
+    }
+}
+
+impl Decodable<'_> for u8 {
+    fn decode(decoder: &mut Decoder<'_>) -> Result<Self> {
+        decoder.byte()
+    }
+}
+
+/// A `Wrapping` value is encoded exactly as the value it wraps.
+impl<T: Encodable> Encodable for Wrapping<T> {
+    fn encoded_length(&self) -> Result<Length> {
+        self.0.encoded_length()
+    }
+
+    /// Encode this value as BER-TLV using the provided [`Encoder`].
+    fn encode(&self, encoder: &mut Encoder<'_>) -> Result<()> {
+        self.0.encode(encoder)
+    }
+}
+
+impl<'a, T: Decodable<'a>> Decodable<'a> for Wrapping<T> {
+    fn decode(decoder: &mut Decoder<'a>) -> Result<Self> {
+        decoder.decode().map(Wrapping)
+    }
+}
+
+/// Nonzero integers are encoded as their big-endian bytes, i.e. as fixed-width raw content.
+///
+/// Decoding a zero value fails with [`ErrorKind::InvalidValue`].
+macro_rules! impl_nonzero {
+    ($($NonZero:ty: $int:ty, $N:literal),*) => {
+        $(
+            impl Encodable for $NonZero {
+                fn encoded_length(&self) -> Result<Length> {
+                    Ok(($N as u8).into())
+                }
+
+                /// Encode this value as BER-TLV using the provided [`Encoder`].
+                fn encode(&self, encoder: &mut Encoder<'_>) -> Result<()> {
+                    encoder.bytes(&self.get().to_be_bytes())
+                }
+            }
+
+            impl Decodable<'_> for $NonZero {
+                fn decode(decoder: &mut Decoder<'_>) -> Result<Self> {
+                    let bytes: [u8; $N] = decoder.decode()?;
+                    Self::new(<$int>::from_be_bytes(bytes))
+                        .ok_or_else(|| ErrorKind::InvalidValue { tag: None }.into())
+                }
+            }
+        )*
+    }
+}
+
+impl_nonzero!(
+    NonZeroU8: u8, 1,
+    NonZeroU16: u16, 2,
+    NonZeroU32: u32, 4,
+    NonZeroU64: u64, 8
+);
+
+/// Tuples are encoded as the sequence of their elements, without a tag of their own,
+/// and decoded element by element from the current position.
+macro_rules! impl_tuple {
+    ($(($($T:ident $t:ident),+)),*) => {
+        $(
+            impl<$($T: Encodable),+> Encodable for ($($T,)+) {
+                fn encoded_length(&self) -> Result<Length> {
+                    let ($($t,)+) = self;
+                    Length::try_from(&[$($t as &dyn Encodable),+][..])
+                }
+
+                /// Encode this value as BER-TLV using the provided [`Encoder`].
+                fn encode(&self, encoder: &mut Encoder<'_>) -> Result<()> {
+                    let ($($t,)+) = self;
+                    $(encoder.encode($t)?;)+
+                    Ok(())
+                }
+            }
+
+            impl<'a, $($T: Decodable<'a>),+> Decodable<'a> for ($($T,)+) {
+                fn decode(decoder: &mut Decoder<'a>) -> Result<Self> {
+                    Ok(($(decoder.decode::<$T>()?,)+))
+                }
+            }
+        )*
     }
+}
+
+impl_tuple!(
+    (A a, B b),
+    (A a, B b, C c),
+    (A a, B b, C c, D d),
+    (A a, B b, C c, D d, E e),
+    (A a, B b, C c, D d, E e, F f)
+);
+
+#[cfg(test)]
+mod tests {
+
+    use super::{Container, Tagged};
+    use crate::{Decodable, Encodable, Error, Result, Tag, TagLike, TaggedSlice};
+    use core::convert::TryFrom;
 
     // The types [u8; 2], [u8; 3], [u8; 4] stand in here for any types for the fields
     // of a struct that are Decodable + Encodable. This means they can decode to/encode from
@@ -402,12 +753,11 @@ mod tests {
         where
             F: FnOnce(&[&dyn Encodable]) -> Result<T>,
         {
-            // both approaches equivalent
+            // raw slices and `Encodable` values are tagged the same way
             field_encoder(&[
-                &(Tag::try_from(0x01).unwrap().with_value(&self.x.as_ref())),
-                // &self.x.tagged(Tag::try_from(0x11).unwrap()),
-                &self.y.as_ref().tagged(Tag::try_from(0x02).unwrap()),
-                &self.z.as_ref().tagged(Tag::try_from(0x03).unwrap()),
+                &Tag::try_from(0x01).unwrap().with_value(&self.x),
+                &Tag::try_from(0x02).unwrap().with_value(&self.y[..]),
+                &Tag::try_from(0x03).unwrap().with_value(&self.z[..]),
             ])
         }
     }
@@ -468,8 +818,8 @@ mod tests {
             F: FnOnce(&[&dyn Encodable]) -> Result<Z>,
         {
             field_encoder(&[
-                &self.s.tagged(Tag::try_from(0x1).unwrap()),
-                &self.t.as_ref().tagged(Tag::try_from(0x2).unwrap()),
+                &Tag::try_from(0x1).unwrap().with_value(&self.s),
+                &Tag::try_from(0x2).unwrap().with_value(&self.t[..]),
             ])
         }
     }
@@ -503,6 +853,86 @@ mod tests {
         assert_eq!(t, t2);
     }
 
+    /// Claims one more byte than it encodes
+    #[cfg(feature = "alloc")]
+    struct Misreported(&'static [u8]);
+
+    #[cfg(feature = "alloc")]
+    impl Encodable for Misreported {
+        fn encoded_length(&self) -> Result<crate::Length> {
+            crate::Length::try_from(self.0.len() + 1)
+        }
+
+        fn encode(&self, encoder: &mut crate::Encoder<'_>) -> Result<()> {
+            encoder.encode(self.0)?;
+            // overshoot when the content is not empty
+            encoder.encode(&self.0[..1.min(self.0.len())])?;
+            encoder.encode(&self.0[..1.min(self.0.len())])
+        }
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn encode_to_vec_failure() {
+        // writing more than `encoded_length` fails without a panic
+        let mut buf = alloc::vec![0xAA];
+        assert!(Misreported(&[1, 2]).encode_to_vec(&mut buf).is_err());
+        assert_eq!(buf, [0xAA]);
+    }
+
+    #[cfg(all(feature = "alloc", debug_assertions))]
+    #[test]
+    #[should_panic(
+        expected = "`encoded_length` of `flexiber::traits::tests::Misreported` does not match"
+    )]
+    fn encode_to_vec_underlength() {
+        Misreported(&[]).to_vec().ok();
+    }
+
+    #[cfg(all(feature = "alloc", not(debug_assertions)))]
+    #[test]
+    fn encode_to_vec_underlength() {
+        let mut buf = alloc::vec![0xAA];
+        assert_eq!(
+            Misreported(&[]).encode_to_vec(&mut buf).unwrap_err().kind(),
+            crate::ErrorKind::Underlength {
+                expected: 1u8.into(),
+                actual: 0u8.into()
+            }
+        );
+        assert_eq!(buf, [0xAA]);
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn nesty_hex_dump() {
+        use alloc::string::ToString;
+
+        let t = T {
+            s: S {
+                x: [1, 2],
+                y: [3, 4, 5],
+                z: [6, 7, 8, 9],
+            },
+            t: [0xA, 0xB, 0xC],
+        };
+        let mut buf = [0u8; 32];
+        let encoded = t.encode_to_slice(&mut buf).unwrap();
+
+        assert_eq!(
+            crate::hex_dump(encoded).to_string(),
+            "\
+0B (24)
+  01 (17)
+    0A (15)
+      01 (2): 01 02
+      02 (3): 03 04 05
+      03 (4): 06 07 08 09
+  02 (3): 0A 0B 0C
+"
+        );
+    }
+
     // tag 0xCC
     #[derive(Clone, Copy, Debug, Eq, PartialEq)]
     struct T2 {
@@ -539,7 +969,7 @@ mod tests {
         {
             field_encoder(&[
                 &self.s,
-                &self.t.as_ref().tagged(Tag::try_from(0x2).unwrap()),
+                &Tag::try_from(0x2).unwrap().with_value(&self.t[..]),
             ])
         }
     }
@@ -662,4 +1092,476 @@ mod tests {
         let s: Option<S> = decoder.decode().unwrap();
         assert!(s.is_none());
     }
+
+    #[test]
+    fn nested_option() {
+        let s = S {
+            x: [1, 2],
+            y: [3, 4, 5],
+            z: [6, 7, 8, 9],
+        };
+        let mut buf = [0u8; 17];
+        let encoded = Some(Some(s)).encode_to_slice(&mut buf).unwrap();
+        assert_eq!(encoded, s.encode_to_slice(&mut [0u8; 17]).unwrap());
+        assert_eq!(
+            Option::<Option<S>>::from_bytes(encoded).unwrap(),
+            Some(Some(s))
+        );
+
+        let absent: Option<Option<S>> = Some(None);
+        assert_eq!(absent.encoded_length().unwrap(), crate::Length::zero());
+        assert_eq!(absent.encode_to_slice(&mut buf).unwrap(), &[]);
+        assert_eq!(Option::<Option<S>>::from_bytes(&[]).unwrap(), None);
+    }
+
+    #[test]
+    fn option_padding() {
+        let s = S {
+            x: [1, 2],
+            y: [3, 4, 5],
+            z: [6, 7, 8, 9],
+        };
+        // two objects separated by filler, followed by filler
+        let mut buf = [0u8; 37];
+        s.encode_to_slice(&mut buf[..17]).unwrap();
+        s.encode_to_slice(&mut buf[19..36]).unwrap();
+        buf[36] = 0xFF;
+
+        let mut decoder = crate::Decoder::new(&buf).with_padding();
+        let first: Option<S> = decoder.decode().unwrap();
+        let second: Option<S> = decoder.decode().unwrap();
+        let third: Option<S> = decoder.decode().unwrap();
+        assert_eq!((first, second, third), (Some(s), Some(s), None));
+        assert!(decoder.finish(()).is_ok());
+
+        // without padding, the filler is read as a universal tag
+        let mut decoder = crate::Decoder::new(&buf);
+        let first: Option<S> = decoder.decode().unwrap();
+        let second: Option<S> = decoder.decode().unwrap();
+        assert_eq!((first, second), (Some(s), None));
+        assert!(decoder.finish(()).is_err());
+    }
+
+    #[test]
+    fn from_bytes_partial() {
+        let s = S {
+            x: [1, 2],
+            y: [3, 4, 5],
+            z: [6, 7, 8, 9],
+        };
+        let mut buf = [0u8; 1024];
+        let len = s.encode_to_slice(&mut buf).unwrap().len();
+        buf[len..][..3].copy_from_slice(&[0x05, 0x01, 0xFF]);
+
+        let (s2, rest) = S::from_bytes_partial(&buf[..len + 3]).unwrap();
+        assert_eq!(s, s2);
+        assert_eq!(rest, &[0x05, 0x01, 0xFF]);
+
+        let (s3, rest) = S::from_bytes_partial(&buf[..len]).unwrap();
+        assert_eq!(s, s3);
+        assert!(rest.is_empty());
+
+        assert!(S::from_bytes(&buf[..len + 3]).is_err());
+    }
+
+    #[test]
+    fn for_each_field() {
+        let s = S {
+            x: [1, 2],
+            y: [3, 4, 5],
+            z: [6, 7, 8, 9],
+        };
+        let t = T2 {
+            s,
+            t: [0xA, 0xB, 0xC],
+        };
+        assert_eq!(s.field_count().unwrap(), 3);
+        assert_eq!(t.field_count().unwrap(), 2);
+
+        // concatenate the fields of both containers
+        let mut buf = [0u8; 1024];
+        let mut encoder = crate::Encoder::new(&mut buf);
+        s.for_each_field(&mut |field| field.encode(&mut encoder))
+            .unwrap();
+        t.for_each_field(&mut |field| field.encode(&mut encoder))
+            .unwrap();
+        let encoded = encoder.finish().unwrap();
+
+        assert_eq!(
+            encoded,
+            &[
+                0x01, 2, 1, 2, 0x02, 3, 3, 4, 5, 0x03, 4, 6, 7, 8, 9, 0x0A, 15, 0x01, 2, 1, 2,
+                0x02, 3, 3, 4, 5, 0x03, 4, 6, 7, 8, 9, 0x2, 3, 0xA, 0xB, 0xC
+            ],
+        );
+    }
+
+    #[test]
+    fn nonzero() {
+        use core::num::{NonZeroU16, NonZeroU32};
+
+        let mut buf = [0u8; 8];
+        let x = NonZeroU32::new(0x0102_0304).unwrap();
+        let encoded = x.encode_to_slice(&mut buf).unwrap();
+        assert_eq!(encoded, &[1, 2, 3, 4]);
+        assert_eq!(NonZeroU32::from_bytes(encoded).unwrap(), x);
+
+        let err = NonZeroU16::from_bytes(&[0, 0]).unwrap_err();
+        assert_eq!(err.kind(), crate::ErrorKind::InvalidValue { tag: None });
+    }
+
+    #[test]
+    fn wrapping() {
+        use core::num::{NonZeroU16, Wrapping};
+
+        let mut buf = [0u8; 8];
+        let x = Wrapping(NonZeroU16::new(0x0102).unwrap());
+        let encoded = Tag::OCTET_STRING
+            .with_value(&x)
+            .encode_to_slice(&mut buf)
+            .unwrap();
+        assert_eq!(encoded, &[0x04, 2, 1, 2]);
+
+        let mut decoder = crate::Decoder::new(encoded);
+        let decoded: Wrapping<NonZeroU16> = decoder.decode_tagged_value(Tag::OCTET_STRING).unwrap();
+        assert_eq!(decoded, x);
+    }
+
+    #[test]
+    fn borrowed_array() {
+        let bytes = [0u8; 64];
+        let array: &[u8; 64] = Decodable::from_bytes(&bytes).unwrap();
+        assert!(core::ptr::eq(array, &bytes));
+
+        let (array, rest) = <&[u8; 2]>::from_bytes_partial(&[1, 2, 3]).unwrap();
+        assert_eq!(array, &[1, 2]);
+        assert_eq!(rest, &[3]);
+
+        let err = <&[u8; 4]>::from_bytes(&[1, 2, 3]).unwrap_err();
+        assert_eq!(err.kind(), crate::ErrorKind::Truncated);
+    }
+
+    #[test]
+    fn borrowed_content() {
+        let buf: &[u8] = &[0x0C, 2, b'h', b'i', 0x04, 3, 1, 2, 3];
+        let mut decoder = crate::Decoder::new(buf);
+        let text: &str = decoder.decode_tagged_value(Tag::UTF8_STRING).unwrap();
+        let bytes: &[u8] = decoder.decode_tagged_value(Tag::OCTET_STRING).unwrap();
+        assert_eq!((text, bytes), ("hi", &buf[6..]));
+        assert!(decoder.finish(()).is_ok());
+
+        let mut encoded = [0u8; 9];
+        let mut encoder = crate::Encoder::new(&mut encoded);
+        encoder.encode(&Tag::UTF8_STRING.with_value(&text)).unwrap();
+        encoder
+            .encode(&Tag::OCTET_STRING.with_value(&bytes))
+            .unwrap();
+        assert_eq!(encoder.finish().unwrap(), buf);
+
+        let mut decoder = crate::Decoder::new(&[0x0C, 1, 0xFF]);
+        let err = decoder
+            .decode_tagged_value::<_, &str>(Tag::UTF8_STRING)
+            .unwrap_err();
+        assert_eq!(
+            err.kind(),
+            crate::ErrorKind::InvalidValue {
+                tag: Some(Tag::UTF8_STRING)
+            }
+        );
+        assert_eq!(
+            <&str>::from_bytes(&[0xFF]).unwrap_err().kind(),
+            crate::ErrorKind::InvalidValue { tag: None }
+        );
+    }
+
+    #[test]
+    fn truncated_arrays() {
+        let kind = |result: Result<()>| result.unwrap_err().kind();
+        let bytes: &[u8] = &[1, 2, 3];
+        assert_eq!(
+            kind(<[u8; 4]>::from_bytes(bytes).map(drop)),
+            crate::ErrorKind::Truncated
+        );
+        assert_eq!(
+            kind(<&[u8; 4]>::from_bytes(bytes).map(drop)),
+            crate::ErrorKind::Truncated
+        );
+        assert_eq!(
+            kind(crate::Bitmap::<4>::from_bytes(bytes).map(drop)),
+            crate::ErrorKind::Truncated
+        );
+
+        // tagged, with a length exceeding the array
+        let mut decoder = crate::Decoder::new(&[0x04, 3, 1, 2, 3]);
+        assert!(decoder
+            .decode_tagged_value::<_, [u8; 2]>(Tag::OCTET_STRING)
+            .is_err());
+        let mut decoder = crate::Decoder::new(&[0x04, 1, 1]);
+        assert!(decoder
+            .decode_tagged_value::<_, &[u8; 2]>(Tag::OCTET_STRING)
+            .is_err());
+    }
+
+    #[test]
+    fn unit() {
+        let mut buf = [0u8; 2];
+        let encoded = Tag::NULL.with_value(&()).encode_to_slice(&mut buf).unwrap();
+        assert_eq!(encoded, &[0x05, 0x00]);
+
+        let mut decoder = crate::Decoder::new(&[0x05, 0x00, 0x05, 0x01, 0x00]);
+        let _: () = decoder.decode_tagged_value(Tag::NULL).unwrap();
+        let err = decoder.decode_tagged_value::<_, ()>(Tag::NULL).unwrap_err();
+        assert!(matches!(err.kind(), crate::ErrorKind::TrailingData { .. }));
+    }
+
+    #[test]
+    fn byte() {
+        let mut buf = [0u8; 3];
+        let encoded = Tag::OCTET_STRING
+            .with_value(&0x9Bu8)
+            .encode_to_slice(&mut buf)
+            .unwrap();
+        assert_eq!(encoded, &[0x04, 1, 0x9B]);
+
+        let mut decoder = crate::Decoder::new(encoded);
+        let byte: u8 = decoder.decode_tagged_value(Tag::OCTET_STRING).unwrap();
+        assert_eq!(byte, 0x9B);
+        assert!(u8::from_bytes(&[]).is_err());
+    }
+
+    #[test]
+    fn bool_array() {
+        let mut buf = [0u8; 4];
+        let flags = [
+            true, false, true, false, false, false, false, true, false, true,
+        ];
+        let encoded = flags.encode_to_slice(&mut buf).unwrap();
+        assert_eq!(encoded, &[0b1010_0001, 0b0100_0000]);
+        assert_eq!(<[bool; 10]>::from_bytes(encoded).unwrap(), flags);
+
+        let flags = [true; 8];
+        let encoded = flags.encode_to_slice(&mut buf).unwrap();
+        assert_eq!(encoded, &[0xFF]);
+        assert_eq!(<[bool; 8]>::from_bytes(encoded).unwrap(), flags);
+
+        assert!([false; 0].encode_to_slice(&mut buf).unwrap().is_empty());
+
+        // unused trailing bits must be zero
+        let err = <[bool; 10]>::from_bytes(&[0, 0b0010_0000]).unwrap_err();
+        assert_eq!(err.kind(), crate::ErrorKind::InvalidValue { tag: None });
+    }
+
+    #[test]
+    fn overlong_slice() {
+        let slice = &[0u8; 70_000][..];
+        let overlength = crate::ErrorKind::Overlength {
+            needed: 70_000,
+            available: 0xFFFF,
+        };
+        assert_eq!(slice.encoded_length().unwrap_err().kind(), overlength);
+
+        // encoding directly errors the same way, before writing anything
+        let mut buf = [0u8; 70_000];
+        let mut encoder = crate::Encoder::new(&mut buf);
+        assert_eq!(slice.encode(&mut encoder).unwrap_err().kind(), overlength);
+        assert_eq!(
+            TaggedSlice::from(Tag::OCTET_STRING, slice)
+                .unwrap_err()
+                .kind(),
+            overlength
+        );
+        assert_eq!(
+            Tag::OCTET_STRING
+                .with_value(slice)
+                .encoded_length()
+                .unwrap_err()
+                .kind(),
+            overlength
+        );
+    }
+
+    #[test]
+    fn trait_object() {
+        let value = TaggedSlice::from(Tag::OCTET_STRING, &[1, 2]).unwrap();
+        let encodable: &dyn Encodable = &value;
+
+        let mut buf = [0u8; 8];
+        assert_eq!(encodable.encoded_length().unwrap(), 4usize);
+        assert_eq!(
+            encodable.encode_to_slice(&mut buf).unwrap(),
+            &[0x04, 2, 1, 2]
+        );
+        assert_eq!(
+            (&encodable).encode_to_slice(&mut buf).unwrap(),
+            &[0x04, 2, 1, 2]
+        );
+
+        let some: Option<&dyn Encodable> = Some(encodable);
+        assert_eq!(some.encode_to_slice(&mut buf).unwrap(), &[0x04, 2, 1, 2]);
+        let none: Option<&dyn Encodable> = None;
+        assert!(none.encode_to_slice(&mut buf).unwrap().is_empty());
+    }
+
+    // tag 0x0D
+    struct Borrowed<'a> {
+        // untagged
+        s: Option<&'a S>,
+        // tag 0x01
+        label: Option<&'a str>,
+        // tag 0x02
+        t: Option<&'a T>,
+    }
+
+    impl Tagged for Borrowed<'_> {
+        fn tag() -> Tag {
+            Tag::try_from(0x0D).unwrap()
+        }
+    }
+
+    impl Container for Borrowed<'_> {
+        fn fields<F, Z>(&self, field_encoder: F) -> Result<Z>
+        where
+            F: FnOnce(&[&dyn Encodable]) -> Result<Z>,
+        {
+            field_encoder(&[
+                &self.s.map(|s| s as &dyn Encodable),
+                &self
+                    .label
+                    .map(|label| Tag::try_from(0x1).unwrap().with_value(label)),
+                &self.t.map(|t| Tag::try_from(0x2).unwrap().with_value(t)),
+            ])
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "heapless")]
+    fn heapless_overlength() {
+        use crate::Length;
+
+        let mut vec = heapless::Vec::<u8, 70_000>::new();
+        vec.resize_default(Length::max()).unwrap();
+        assert_eq!(vec.encoded_length().unwrap(), Length::max());
+        vec.resize_default(Length::max() + 1).unwrap();
+        let overlength = crate::ErrorKind::Overlength {
+            needed: Length::max() + 1,
+            available: Length::max(),
+        };
+        assert_eq!(vec.encoded_length().unwrap_err().kind(), overlength);
+        let mut buf = [0u8; 70_000];
+        let mut encoder = crate::Encoder::new(&mut buf);
+        assert_eq!(vec.encode(&mut encoder).unwrap_err().kind(), overlength);
+
+        let mut string = heapless::String::<70_000>::new();
+        let text = core::str::from_utf8(&buf[..Length::max() + 1]).unwrap();
+        string.push_str(text).unwrap();
+        assert_eq!(string.encoded_length().unwrap_err().kind(), overlength);
+
+        let mut short = heapless::String::<4>::new();
+        short.push_str("ok").unwrap();
+        assert_eq!(
+            Tag::UTF8_STRING
+                .with_value(&short)
+                .encode_to_slice(&mut buf[..8])
+                .unwrap(),
+            &[0x0C, 2, b'o', b'k']
+        );
+    }
+
+    #[test]
+    fn mutable_slice() {
+        let mut scratch = [1u8, 2, 3];
+        let mut buf = [0u8; 8];
+        assert_eq!(
+            Tag::OCTET_STRING
+                .with_value(&&mut scratch[1..])
+                .encode_to_slice(&mut buf)
+                .unwrap(),
+            &[0x04, 2, 2, 3]
+        );
+    }
+
+    #[test]
+    fn tuples() {
+        let mut buf = [0u8; 16];
+
+        let octets = TaggedSlice::from(Tag::OCTET_STRING, &[1, 2]).unwrap();
+        let null = TaggedSlice::from(Tag::NULL, &[]).unwrap();
+        let pair = (octets, null);
+        let encoded = pair.encode_to_slice(&mut buf).unwrap();
+        assert_eq!(encoded, &[0x04, 2, 1, 2, 0x05, 0]);
+        assert_eq!(pair.encoded_length().unwrap(), 6usize);
+        assert_eq!(
+            <(TaggedSlice<'_>, TaggedSlice<'_>)>::from_bytes(encoded).unwrap(),
+            pair
+        );
+
+        // borrowed elements point into the decoded bytes
+        let (tagged, rest): (TaggedSlice<'_>, &[u8]) = Decodable::from_bytes(encoded).unwrap();
+        assert_eq!(tagged, octets);
+        assert!(core::ptr::eq(rest, &encoded[4..]));
+
+        let six = (1u8, 2u8, 3u8, [4u8, 5], 6u8, null);
+        let encoded = six.encode_to_slice(&mut buf).unwrap();
+        assert_eq!(encoded, &[1, 2, 3, 4, 5, 6, 0x05, 0]);
+        assert_eq!(Decodable::from_bytes(encoded), Ok(six));
+
+        let mut decoder = crate::Decoder::new(&[1, 2, 3]);
+        let (a, b): (u8, [u8; 2]) = decoder.decode().unwrap();
+        assert_eq!((a, b), (1, [2, 3]));
+        assert!(decoder.is_finished());
+    }
+
+    #[test]
+    fn tagged_reference() {
+        fn tag_of<X: Tagged>() -> Tag {
+            X::tag()
+        }
+
+        assert_eq!(tag_of::<&S>(), S::tag());
+        assert_eq!(tag_of::<&&S>(), S::tag());
+        assert_eq!(<&T>::tag(), Tag::try_from(0x0B).unwrap());
+    }
+
+    #[test]
+    fn optional_borrowed_fields() {
+        let s = S {
+            x: [1, 2],
+            y: [3, 4, 5],
+            z: [6, 7, 8, 9],
+        };
+        let t = T {
+            s,
+            t: [0xA, 0xB, 0xC],
+        };
+        let mut buf = [0u8; 64];
+
+        let borrowed = Borrowed {
+            s: Some(&s),
+            label: Some("ok"),
+            t: None,
+        };
+        assert_eq!(
+            borrowed.encode_to_slice(&mut buf).unwrap(),
+            &[
+                0x0D, 21, 0x0A, 15, 0x01, 2, 1, 2, 0x02, 3, 3, 4, 5, 0x03, 4, 6, 7, 8, 9, 0x01, 2,
+                b'o', b'k'
+            ],
+        );
+
+        let borrowed = Borrowed {
+            s: None,
+            label: None,
+            t: Some(&t),
+        };
+        let encoded = borrowed.encode_to_slice(&mut buf).unwrap();
+        assert_eq!(encoded[..4], [0x0D, 28, 0x02, 26]);
+        assert_eq!(T::from_bytes(&encoded[4..]).unwrap(), t);
+
+        let empty = Borrowed {
+            s: None,
+            label: None,
+            t: None,
+        };
+        assert_eq!(empty.encode_to_slice(&mut buf).unwrap(), &[0x0D, 0]);
+    }
 }
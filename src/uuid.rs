@@ -0,0 +1,55 @@
+use crate::{Decodable, Decoder, Encodable, Encoder, Length, Result};
+use ::uuid::Uuid;
+
+/// A UUID is encoded as its 16 raw bytes, e.g. as the GUID in a PIV card holder unique identifier.
+impl Encodable for Uuid {
+    fn encoded_length(&self) -> Result<Length> {
+        self.as_bytes().encoded_length()
+    }
+
+    /// Encode this value as BER-TLV using the provided [`Encoder`].
+    fn encode(&self, encoder: &mut Encoder<'_>) -> Result<()> {
+        self.as_bytes().encode(encoder)
+    }
+}
+
+/// Decodes exactly 16 bytes, failing with [`ErrorKind::Truncated`](crate::ErrorKind::Truncated)
+/// if fewer remain.
+impl Decodable<'_> for Uuid {
+    fn decode(decoder: &mut Decoder<'_>) -> Result<Self> {
+        <[u8; 16]>::decode(decoder).map(Uuid::from_bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Decodable, Decoder, Encodable, ErrorKind, SimpleTag, TagLike};
+    use ::uuid::Uuid;
+    use core::convert::TryFrom;
+
+    const GUID: Uuid = Uuid::from_u128(0x67e5_5044_10b1_426f_9247_bb68_0e5f_e0c8);
+    const ENCODED: [u8; 18] = [
+        0x34, 16, 0x67, 0xe5, 0x50, 0x44, 0x10, 0xb1, 0x42, 0x6f, 0x92, 0x47, 0xbb, 0x68, 0x0e,
+        0x5f, 0xe0, 0xc8,
+    ];
+
+    #[test]
+    fn roundtrip() {
+        // the GUID of a PIV card holder unique identifier
+        let tag = SimpleTag::try_from(0x34).unwrap();
+        let mut buf = [0u8; 32];
+        let encoded = tag.with_value(&GUID).encode_to_slice(&mut buf).unwrap();
+        assert_eq!(encoded, ENCODED);
+
+        let mut decoder = Decoder::new(encoded);
+        let decoded: Uuid = decoder.decode_tagged_value(tag).unwrap();
+        assert_eq!(decoded, GUID);
+    }
+
+    #[test]
+    fn exact_length() {
+        let err = <Uuid as Decodable>::from_bytes(&ENCODED[2..17]).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::Truncated);
+        assert!(<Uuid as Decodable>::from_bytes(&ENCODED).is_err());
+    }
+}
@@ -16,6 +16,24 @@ struct S {
     z: [u8; 4],
 }
 
+#[test]
+fn derived_slice_length_mismatch() {
+    // `x` carries 1 instead of 2 content bytes
+    let encoded = &[
+        0x3F, 0x81, 0x2A, 16, 0x11, 1, 1, 0x1F, 0x22, 3, 3, 4, 5, 0x1F, 0x33, 4, 6, 7, 8, 9,
+    ];
+
+    let err = S::from_bytes(encoded).unwrap_err();
+    assert_eq!(
+        err.kind(),
+        ber::ErrorKind::Length {
+            tag: ber::Tag::universal(0x11),
+            expected: Some(2u8.into()),
+            actual: 1u8.into(),
+        }
+    );
+}
+
 #[derive(Clone, Copy, Debug, Decodable, Encodable, Eq, PartialEq)]
 #[tlv(application, number = "0xAA")]
 struct SApp {
@@ -40,7 +58,7 @@ fn derived_reconstruct() {
 
     assert_eq!(
         encoded,
-        &[0x1F, 0x81, 0x2A, 17, 0x11, 2, 1, 2, 0x1F, 0x22, 3, 3, 4, 5, 0x1F, 0x33, 4, 6, 7, 8, 9,],
+        &[0x3F, 0x81, 0x2A, 17, 0x11, 2, 1, 2, 0x1F, 0x22, 3, 3, 4, 5, 0x1F, 0x33, 4, 6, 7, 8, 9,],
     );
 
     let s2 = S::from_bytes(encoded).unwrap();
@@ -60,13 +78,63 @@ fn derived_reconstruct_application() {
 
     assert_eq!(
         encoded,
-        &[0x5F, 0x81, 0x2A, 17, 0x11, 2, 1, 2, 0x1F, 0x22, 3, 3, 4, 5, 0x1F, 0x33, 4, 6, 7, 8, 9,],
+        &[0x7F, 0x81, 0x2A, 17, 0x11, 2, 1, 2, 0x1F, 0x22, 3, 3, 4, 5, 0x1F, 0x33, 4, 6, 7, 8, 9,],
     );
 
     let s2 = SApp::from_bytes(encoded).unwrap();
     assert_eq!(s, s2);
 }
 
+/// `S`, borrowing its fields from the decoded bytes
+#[derive(Clone, Copy, Debug, Decodable, Encodable, Eq, PartialEq)]
+#[tlv(number = "0xAA")]
+struct SRef<'a> {
+    #[tlv(slice, number = "0x11")]
+    x: &'a [u8],
+    #[tlv(slice, number = "0x22")]
+    y: &'a [u8],
+    #[tlv(slice, number = "0x33")]
+    z: &'a [u8],
+}
+
+#[derive(Clone, Copy, Debug, Decodable, Encodable, Eq, PartialEq)]
+#[tlv(number = "0x10")]
+struct TRef<'a> {
+    #[tlv(context, number = "0x1")]
+    s: SRef<'a>,
+    #[tlv(slice, number = "0x44")]
+    big: &'a [u8],
+}
+
+#[test]
+fn derived_zero_copy() {
+    let encoded: &[u8] = &[
+        0x30, 29, 0x81, 21, 0x3F, 0x81, 0x2A, 17, 0x11, 2, 1, 2, 0x1F, 0x22, 3, 3, 4, 5, 0x1F,
+        0x33, 4, 6, 7, 8, 9, 0x1F, 0x44, 3, 0xEE, 0xEE, 0xEE,
+    ];
+
+    // all fields, including the ones of the nested struct, point into `encoded`
+    let t = TRef::from_bytes(encoded).unwrap();
+    assert_eq!(t.s.x, &[1, 2]);
+    assert!(core::ptr::eq(t.s.x, &encoded[10..12]));
+    assert!(core::ptr::eq(t.s.y, &encoded[15..18]));
+    assert!(core::ptr::eq(t.s.z, &encoded[21..25]));
+    assert!(core::ptr::eq(t.big, &encoded[28..]));
+
+    let s = S {
+        x: [1, 2],
+        y: [3, 4, 5],
+        z: [6, 7, 8, 9],
+    };
+    let mut buf = [0u8; 32];
+    let mut other_buf = [0u8; 32];
+    assert_eq!(
+        t.s.encode_to_slice(&mut buf).unwrap(),
+        s.encode_to_slice(&mut other_buf).unwrap()
+    );
+    assert_eq!(t.encode_to_slice(&mut buf).unwrap(), encoded);
+}
+
 #[derive(Clone, Copy, Debug, Decodable, Encodable, Eq, PartialEq)]
 #[tlv(constructed, number = "0x10")]
 struct T {
@@ -148,7 +216,693 @@ fn derive_untagged() {
     assert_eq!(t, t2);
 }
 
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Debug, Decodable, Encodable, Eq, PartialEq)]
+struct Pair {
+    #[tlv(context, constructed, number = "0x0")]
+    left: Repeated,
+    #[tlv(context, constructed, number = "0x1")]
+    right: Repeated,
+}
+
+#[test]
+fn derive_untagged_tagged_fields() {
+    let pair = Pair {
+        left: Repeated {
+            first: [1, 2],
+            second: [3, 4],
+        },
+        right: Repeated {
+            first: [5, 6],
+            second: [7, 8],
+        },
+    };
+
+    let mut buf = [0u8; 32];
+    let encoded = pair.encode_to_slice(&mut buf).unwrap();
+    assert_eq!(
+        encoded,
+        &[
+            0xA0, 10, 0x32, 8, 0x01, 2, 1, 2, 0x01, 2, 3, 4, 0xA1, 10, 0x32, 8, 0x01, 2, 5, 6,
+            0x01, 2, 7, 8
+        ]
+    );
+    assert_eq!(Pair::from_bytes(encoded).unwrap(), pair);
+
+    // the fields are framed by their own tags, anything after them is left over
+    let mut trailing = [0u8; 25];
+    trailing[..24].copy_from_slice(encoded);
+    assert_eq!(
+        Pair::from_bytes(&trailing).unwrap_err().kind(),
+        ber::ErrorKind::TrailingData {
+            decoded: 24u8.into(),
+            remaining: 1u8.into(),
+        }
+    );
+    assert_eq!(
+        Pair::from_bytes_partial(&trailing).unwrap(),
+        (pair, &[0u8][..])
+    );
+
+    // content of a field must be consumed entirely
+    let mut overlong = [0u8; 25];
+    overlong[..2].copy_from_slice(&[0xA0, 11]);
+    overlong[2..12].copy_from_slice(&encoded[2..12]);
+    overlong[13..].copy_from_slice(&encoded[12..]);
+    assert_eq!(
+        Pair::from_bytes(&overlong).unwrap_err().kind(),
+        ber::ErrorKind::TrailingData {
+            decoded: 10u8.into(),
+            remaining: 1u8.into(),
+        }
+    );
+}
+
+#[derive(Clone, Copy, Debug, Decodable, Encodable, Eq, PartialEq)]
+#[tlv(constructed, number = "0x10")]
+struct OptionalNested {
+    #[tlv(context, constructed, number = "0x1")]
+    s: Option<S>,
+    #[tlv(slice, number = "0x02")]
+    x: [u8; 2],
+}
+
+#[test]
+fn derive_optional_nested() {
+    let s = S {
+        x: [1, 2],
+        y: [3, 4, 5],
+        z: [6, 7, 8, 9],
+    };
+    let mut buf = [0u8; 64];
+
+    let present = OptionalNested {
+        s: Some(s),
+        x: [0xA, 0xB],
+    };
+    let encoded = present.encode_to_slice(&mut buf).unwrap();
+    assert_eq!(&encoded[..4], &[0x30, 27, 0xA1, 21]);
+    assert_eq!(&encoded[4..8], &[0x3F, 0x81, 0x2A, 17]);
+    assert_eq!(&encoded[25..], &[0x02, 2, 0xA, 0xB]);
+    assert_eq!(OptionalNested::from_bytes(encoded).unwrap(), present);
+
+    let absent = OptionalNested {
+        s: None,
+        x: [0xA, 0xB],
+    };
+    let encoded = absent.encode_to_slice(&mut buf).unwrap();
+    assert_eq!(encoded, &[0x30, 4, 0x02, 2, 0xA, 0xB]);
+    assert_eq!(OptionalNested::from_bytes(encoded).unwrap(), absent);
+}
+
+#[derive(Clone, Copy, Debug, Decodable, Encodable, Eq, PartialEq)]
+#[tlv(number = "0x12")]
+struct Repeated {
+    #[tlv(slice, number = "0x01")]
+    first: [u8; 2],
+    #[tlv(slice, allow_duplicate, number = "0x01")]
+    second: [u8; 2],
+}
+
+#[test]
+fn derive_allow_duplicate() {
+    let r = Repeated {
+        first: [1, 2],
+        second: [3, 4],
+    };
+
+    let mut buf = [0u8; 16];
+    let encoded = r.encode_to_slice(&mut buf).unwrap();
+    assert_eq!(encoded, &[0x32, 8, 0x01, 2, 1, 2, 0x01, 2, 3, 4]);
+
+    let r2 = Repeated::from_bytes(encoded).unwrap();
+    assert_eq!(r, r2);
+}
+
+#[derive(Clone, Copy, Debug, Decodable, Encodable, Eq, PartialEq)]
+#[tlv(context, number = "0x3")]
+struct DefaultConstructed {
+    #[tlv(slice, number = "0x01")]
+    x: [u8; 1],
+}
+
+#[derive(Clone, Copy, Debug, Decodable, Encodable, Eq, PartialEq)]
+#[tlv(context, primitive, number = "0x3")]
+struct ExplicitlyPrimitive {
+    #[tlv(slice, number = "0x01")]
+    x: [u8; 1],
+}
+
+#[test]
+fn derive_container_tag_constructed_by_default() {
+    let mut buf = [0u8; 16];
+
+    let constructed = DefaultConstructed { x: [7] };
+    let encoded = constructed.encode_to_slice(&mut buf).unwrap();
+    assert_eq!(encoded, &[0xA3, 3, 0x01, 1, 7]);
+    assert_eq!(
+        DefaultConstructed::from_bytes(encoded).unwrap(),
+        constructed
+    );
+
+    let primitive = ExplicitlyPrimitive { x: [7] };
+    let encoded = primitive.encode_to_slice(&mut buf).unwrap();
+    assert_eq!(encoded, &[0x83, 3, 0x01, 1, 7]);
+    assert_eq!(ExplicitlyPrimitive::from_bytes(encoded).unwrap(), primitive);
+
+    // the tags differ, so these do not decode as each other
+    assert!(DefaultConstructed::from_bytes(encoded).is_err());
+}
+
+#[derive(Clone, Copy, Debug, Decodable, Encodable, Eq, PartialEq)]
+#[tlv(constructed, number = "0x10")]
+struct TagBoundaries {
+    #[tlv(slice, context, number = "0x0")]
+    zero: [u8; 1],
+    #[tlv(slice, context, number = "0x1E")]
+    last_short: [u8; 1],
+    #[tlv(slice, context, number = "0x1F")]
+    first_long: [u8; 1],
+    #[tlv(slice, context, number = "0x7F")]
+    last_one_byte: [u8; 1],
+    #[tlv(slice, context, number = "0x80")]
+    first_two_bytes: [u8; 1],
+    #[tlv(slice, context, number = "0x3FFF")]
+    last_two_bytes: [u8; 1],
+}
+
+#[test]
+fn derive_tag_number_boundaries() {
+    let value = TagBoundaries {
+        zero: [0],
+        last_short: [1],
+        first_long: [2],
+        last_one_byte: [3],
+        first_two_bytes: [4],
+        last_two_bytes: [5],
+    };
+
+    let mut buf = [0u8; 32];
+    let encoded = value.encode_to_slice(&mut buf).unwrap();
+    #[rustfmt::skip]
+    assert_eq!(
+        encoded,
+        &[
+            0x30, 24,
+            0x80, 1, 0,
+            0x9E, 1, 1,
+            0x9F, 0x1F, 1, 2,
+            0x9F, 0x7F, 1, 3,
+            0x9F, 0x81, 0x00, 1, 4,
+            0x9F, 0xFF, 0x7F, 1, 5,
+        ]
+    );
+    assert_eq!(TagBoundaries::from_bytes(encoded).unwrap(), value);
+}
+
+#[derive(Clone, Copy, Debug, Decodable, Encodable, Eq, PartialEq)]
+#[tlv(application, number = "0x4", ignore_unknown)]
+struct Extensible {
+    #[tlv(slice, context, number = "0x1")]
+    a: [u8; 1],
+    #[tlv(context, number = "0x2")]
+    b: Option<[u8; 1]>,
+    #[tlv(slice, context, number = "0x3")]
+    c: [u8; 1],
+}
+
+#[test]
+fn derive_ignore_unknown() {
+    let known = Extensible {
+        a: [1],
+        b: Some([2]),
+        c: [3],
+    };
+    let mut buf = [0u8; 16];
+    let encoded = known.encode_to_slice(&mut buf).unwrap();
+    assert_eq!(encoded, &[0x64, 9, 0x81, 1, 1, 0x82, 1, 2, 0x83, 1, 3]);
+    assert_eq!(Extensible::from_bytes(encoded).unwrap(), known);
+
+    // unknown elements before, between and after the fields are skipped
+    let extended = &[
+        0x64, 15, 0x85, 1, 9, 0x81, 1, 1, 0x9F, 0x20, 1, 9, 0x83, 1, 3, 0x86, 0,
+    ];
+    assert_eq!(
+        Extensible::from_bytes(extended).unwrap(),
+        Extensible {
+            a: [1],
+            b: None,
+            c: [3],
+        }
+    );
+
+    // the known fields must still be in order
+    let reordered = &[0x64, 6, 0x83, 1, 3, 0x81, 1, 1];
+    assert!(Extensible::from_bytes(reordered).is_err());
+}
+
+#[derive(Clone, Copy, Debug, Decodable, Encodable, Eq, PartialEq)]
+#[tlv(crate = "ber", number = "0x13")]
+struct RenamedCrate {
+    #[tlv(slice, number = "0x01")]
+    x: [u8; 2],
+}
+
+#[test]
+fn derive_crate_path() {
+    let r = RenamedCrate { x: [1, 2] };
+
+    let mut buf = [0u8; 16];
+    let encoded = r.encode_to_slice(&mut buf).unwrap();
+    assert_eq!(encoded, &[0x33, 4, 0x01, 2, 1, 2]);
+    assert_eq!(RenamedCrate::from_bytes(encoded).unwrap(), r);
+}
+
+/// Nothing from `flexiber` is imported here
+mod clean {
+    #[derive(Clone, Copy, Debug, Eq, PartialEq, flexiber::Decodable, flexiber::Encodable)]
+    #[tlv(simple = "0x55")]
+    pub struct SimpleTagged {
+        #[tlv(simple = "0x01", slice)]
+        pub x: [u8; 2],
+        #[tlv(number = "0x02", slice)]
+        pub y: [u8; 1],
+    }
+}
+
+#[test]
+fn derive_simple_tagged_in_clean_module() {
+    let s = clean::SimpleTagged { x: [1, 2], y: [3] };
+
+    let mut buf = [0u8; 16];
+    let encoded = s.encode_to_slice(&mut buf).unwrap();
+    assert_eq!(encoded, &[0x55, 7, 0x01, 2, 1, 2, 0x02, 1, 3]);
+    assert_eq!(clean::SimpleTagged::from_bytes(encoded).unwrap(), s);
+}
+
+#[test]
+fn derived_simple_slice_length_mismatch() {
+    // `x` carries 1 instead of 2 content bytes, this must not panic
+    let encoded = &[0x55, 6, 0x01, 1, 1, 0x02, 1, 3];
+
+    let err = clean::SimpleTagged::from_bytes(encoded).unwrap_err();
+    assert_eq!(
+        err.kind(),
+        ber::ErrorKind::Length {
+            tag: ber::Tag::universal(0x01),
+            expected: Some(2u8.into()),
+            actual: 1u8.into(),
+        }
+    );
+}
+
+#[derive(Debug, Eq, PartialEq, Decodable, Encodable)]
+#[tlv(simple = "0x55")]
+struct LongSimpleTagged {
+    #[tlv(simple = "0x01", slice)]
+    x: [u8; 300],
+}
+
+#[test]
+fn derive_simple_tagged_long_form() {
+    let s = LongSimpleTagged { x: [0xAA; 300] };
+
+    let mut buf = [0u8; 320];
+    let encoded = s.encode_to_slice(&mut buf).unwrap();
+    assert_eq!(s.encoded_length().unwrap(), 308usize);
+    // both the wrapper and the field use the SIMPLE-TLV long form
+    assert_eq!(
+        &encoded[..8],
+        &[0x55, 0xFF, 0x01, 0x30, 0x01, 0xFF, 0x01, 0x2C]
+    );
+    assert_eq!(LongSimpleTagged::from_bytes(encoded).unwrap(), s);
+
+    // a BER-TLV wrapper length is rejected
+    let mut ber = [0u8; 308];
+    ber[..4].copy_from_slice(&[0x55, 0x82, 0x01, 0x30]);
+    ber[4..].copy_from_slice(&encoded[4..]);
+    assert!(LongSimpleTagged::from_bytes(&ber).is_err());
+
+    // as is a BER-TLV field length
+    let mut ber = [0u8; 308];
+    ber[..8].copy_from_slice(&[0x55, 0xFF, 0x01, 0x30, 0x01, 0x82, 0x01, 0x2C]);
+    ber[8..].copy_from_slice(&encoded[8..]);
+    assert!(LongSimpleTagged::from_bytes(&ber).is_err());
+}
+
+#[derive(Debug, Eq, PartialEq, Decodable, Encodable)]
+#[tlv(application, number = "0x1")]
+struct Borrowed<'a> {
+    #[tlv(number = "0x0C")]
+    label: &'a str,
+    #[tlv(number = "0x04")]
+    data: &'a [u8],
+    #[tlv(context, number = "0x0")]
+    note: Option<&'a str>,
+}
+
+#[test]
+fn derive_borrowed_fields() {
+    let encoded: &[u8] = &[
+        0x61, 13, 0x0C, 3, b'p', b'i', b'n', 0x04, 2, 0xAA, 0xBB, 0x80, 2, b'o', b'k',
+    ];
+
+    // the fields point into `encoded`, without a copy
+    let decoded = Borrowed::from_bytes(encoded).unwrap();
+    assert_eq!(
+        decoded,
+        Borrowed {
+            label: "pin",
+            data: &[0xAA, 0xBB],
+            note: Some("ok"),
+        }
+    );
+    assert_eq!(decoded.data.as_ptr(), encoded[9..].as_ptr());
+
+    let mut buf = [0u8; 16];
+    assert_eq!(decoded.encode_to_slice(&mut buf).unwrap(), encoded);
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Decodable, Encodable)]
+#[tlv(transparent)]
+struct KeyRef(u8);
+
+#[derive(Debug, Eq, PartialEq, Decodable, Encodable)]
+#[tlv(transparent)]
+struct Label<'a> {
+    text: &'a str,
+}
+
+#[derive(Debug, Eq, PartialEq, Decodable, Encodable)]
+#[tlv(number = "0x1C")]
+struct Reference {
+    #[tlv(context, number = "0x3")]
+    key: KeyRef,
+    #[tlv(context, number = "0x4")]
+    fallback: Option<KeyRef>,
+}
+
+#[test]
+fn derive_transparent() {
+    let mut buf = [0u8; 16];
+    assert_eq!(KeyRef(0x9B).encode_to_slice(&mut buf).unwrap(), &[0x9B]);
+    assert_eq!(KeyRef::from_bytes(&[0x9B]).unwrap(), KeyRef(0x9B));
+    assert_eq!(Label::from_bytes(b"ok").unwrap(), Label { text: "ok" });
+
+    // the field of a newtype is framed by the tag of the outer field only
+    let reference = Reference {
+        key: KeyRef(0x9A),
+        fallback: Some(KeyRef(0x9C)),
+    };
+    let encoded = reference.encode_to_slice(&mut buf).unwrap();
+    assert_eq!(encoded, &[0x3C, 6, 0x83, 1, 0x9A, 0x84, 1, 0x9C]);
+    assert_eq!(Reference::from_bytes(encoded).unwrap(), reference);
+}
+
+#[derive(Debug, Eq, PartialEq, Decodable, Encodable)]
+#[tlv(number = "0x10")]
+struct Buf<'a, const N: usize> {
+    #[tlv(slice, number = "0x1")]
+    head: &'a [u8],
+    #[tlv(slice, number = "0x2")]
+    tail: [u8; N],
+}
+
+#[test]
+fn derive_lifetime_and_const_generics() {
+    let encoded: &[u8] = &[0x30, 8, 0x01, 1, 0xAA, 0x02, 3, 1, 2, 3];
+    let buf = Buf::<3>::from_bytes(encoded).unwrap();
+    assert_eq!(
+        buf,
+        Buf {
+            head: &[0xAA],
+            tail: [1, 2, 3]
+        }
+    );
+
+    let mut out = [0u8; 16];
+    assert_eq!(buf.encode_to_slice(&mut out).unwrap(), encoded);
+
+    // the array length is checked against `N`
+    let err = Buf::<2>::from_bytes(encoded).unwrap_err();
+    assert_eq!(
+        err.kind(),
+        ber::ErrorKind::Length {
+            tag: ber::Tag::universal(0x02),
+            expected: Some(2u8.into()),
+            actual: 3u8.into(),
+        }
+    );
+}
+
+#[derive(Debug, Eq, PartialEq, Decodable, Encodable)]
+#[tlv(application, number = "0x3")]
+struct EmptyUnit;
+
+#[derive(Debug, Eq, PartialEq, Decodable, Encodable)]
+#[tlv(context, number = "0x4")]
+struct EmptyBraced {}
+
+#[derive(Debug, Eq, PartialEq, Decodable, Encodable)]
+struct WithEmpty {
+    #[tlv(number = "0x1")]
+    unit: EmptyUnit,
+    #[tlv(number = "0x2")]
+    braced: Option<EmptyBraced>,
+}
+
+#[test]
+fn derive_empty_structs() {
+    let mut buf = [0u8; 16];
+    assert_eq!(EmptyUnit.encode_to_slice(&mut buf).unwrap(), &[0x63, 0x00]);
+    assert_eq!(EmptyUnit::from_bytes(&[0x63, 0x00]).unwrap(), EmptyUnit);
+    assert_eq!(
+        EmptyBraced {}.encode_to_slice(&mut buf).unwrap(),
+        &[0xA4, 0x00]
+    );
+    assert_eq!(
+        EmptyBraced::from_bytes(&[0xA4, 0x00]).unwrap(),
+        EmptyBraced {}
+    );
+
+    // content is rejected
+    assert!(EmptyUnit::from_bytes(&[0x63, 0x01, 0x00]).is_err());
+
+    let with_empty = WithEmpty {
+        unit: EmptyUnit,
+        braced: Some(EmptyBraced {}),
+    };
+    let encoded = with_empty.encode_to_slice(&mut buf).unwrap();
+    assert_eq!(encoded, &[0x01, 2, 0x63, 0, 0x02, 2, 0xA4, 0]);
+    assert_eq!(WithEmpty::from_bytes(encoded).unwrap(), with_empty);
+}
+
+// generated code must not rely on the prelude or imports at the call site
+#[no_implicit_prelude]
+mod no_prelude {
+    #[derive(
+        ::core::clone::Clone,
+        ::core::marker::Copy,
+        ::core::fmt::Debug,
+        ::core::cmp::PartialEq,
+        ::flexiber::Decodable,
+        ::flexiber::Encodable,
+    )]
+    #[tlv(number = "0x10")]
+    pub struct Inner {
+        #[tlv(slice, number = "0x01")]
+        pub a: [u8; 2],
+    }
+
+    #[derive(
+        ::core::fmt::Debug, ::core::cmp::PartialEq, ::flexiber::Decodable, ::flexiber::Encodable,
+    )]
+    pub struct Untagged {
+        #[tlv(simple = "0x02", slice)]
+        pub b: [u8; 1],
+        #[tlv(context, constructed, number = "0x0")]
+        pub inner: ::core::option::Option<Inner>,
+    }
+}
+
+#[test]
+fn derive_without_prelude() {
+    let mut buf = [0u8; 16];
+
+    let present = no_prelude::Untagged {
+        b: [9],
+        inner: Some(no_prelude::Inner { a: [1, 2] }),
+    };
+    let encoded = present.encode_to_slice(&mut buf).unwrap();
+    assert_eq!(encoded, &[0x02, 1, 9, 0xA0, 6, 0x30, 4, 0x01, 2, 1, 2]);
+    assert_eq!(no_prelude::Untagged::from_bytes(encoded).unwrap(), present);
+
+    let absent = no_prelude::Untagged {
+        b: [9],
+        inner: None,
+    };
+    let encoded = absent.encode_to_slice(&mut buf).unwrap();
+    assert_eq!(encoded, &[0x02, 1, 9]);
+    assert_eq!(no_prelude::Untagged::from_bytes(encoded).unwrap(), absent);
+}
+
+#[derive(Clone, Copy, Debug, Decodable, Encodable, Eq, PartialEq)]
+#[repr(u8)]
+enum State {
+    Idle,
+    Busy,
+    Failed = 0x80,
+}
+
+#[derive(Clone, Copy, Debug, Decodable, Encodable, Eq, PartialEq)]
+#[tlv(constructed, number = "0x10")]
+struct Status {
+    #[tlv(context, number = "0x0")]
+    state: State,
+}
+
+#[test]
+fn derive_enumerated() {
+    let mut buf = [0u8; 16];
+    for (state, encoded) in [
+        (State::Idle, &[0x0A, 1, 0][..]),
+        (State::Busy, &[0x0A, 1, 1]),
+        (State::Failed, &[0x0A, 2, 0, 0x80]),
+    ] {
+        assert_eq!(state.encode_to_slice(&mut buf).unwrap(), encoded);
+        assert_eq!(State::from_bytes(encoded).unwrap(), state);
+    }
+
+    assert_eq!(
+        State::from_bytes(&[0x0A, 1, 2]).unwrap_err().kind(),
+        ber::ErrorKind::InvalidValue {
+            tag: Some(ber::Tag::ENUMERATED)
+        }
+    );
+
+    let status = Status { state: State::Busy };
+    let encoded = status.encode_to_slice(&mut buf).unwrap();
+    assert_eq!(encoded, &[0x30, 5, 0x80, 3, 0x0A, 1, 1]);
+    assert_eq!(Status::from_bytes(encoded).unwrap(), status);
+}
+
+#[derive(Clone, Copy, Debug, Decodable, Encodable, Eq, PartialEq)]
+#[tlv(application)]
+#[tlv(number = "0x1E")]
+struct SplitAttributes {
+    #[tlv(slice)]
+    #[tlv(application)]
+    #[tlv(number = "0x2f")]
+    policy: [u8; 2],
+    #[tlv(slice)]
+    #[tlv(simple = "0x55")]
+    simple: [u8; 1],
+    // the last setting wins
+    #[tlv(slice, context, number = "0x1")]
+    #[tlv(number = "0x2")]
+    overridden: [u8; 1],
+}
+
+#[derive(Clone, Copy, Debug, Decodable, Encodable, Eq, PartialEq)]
+#[tlv(application, number = "0x1E")]
+struct JoinedAttributes {
+    #[tlv(slice, application, number = "0x2f")]
+    policy: [u8; 2],
+    #[tlv(slice, simple = "0x55")]
+    simple: [u8; 1],
+    #[tlv(slice, context, number = "0x2")]
+    overridden: [u8; 1],
+}
+
+#[test]
+fn derive_split_attributes() {
+    let split = SplitAttributes {
+        policy: [0x40, 0x00],
+        simple: [1],
+        overridden: [2],
+    };
+    let joined = JoinedAttributes {
+        policy: [0x40, 0x00],
+        simple: [1],
+        overridden: [2],
+    };
+
+    let mut buf = [0u8; 16];
+    let encoded = split.encode_to_slice(&mut buf).unwrap();
+    assert_eq!(
+        encoded,
+        &[0x7E, 11, 0x5F, 0x2F, 2, 0x40, 0x00, 0x55, 1, 1, 0x82, 1, 2]
+    );
+    let mut joined_buf = [0u8; 16];
+    assert_eq!(joined.encode_to_slice(&mut joined_buf).unwrap(), encoded);
+    assert_eq!(SplitAttributes::from_bytes(encoded).unwrap(), split);
+}
+
+/// Hand-written bitfield, decoded from the content of its field only
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+struct Flags {
+    read: bool,
+    write: bool,
+}
+
+impl Decodable<'_> for Flags {
+    fn decode(decoder: &mut ber::Decoder<'_>) -> ber::Result<Self> {
+        let byte = decoder.read_bytes(1)?[0];
+        if byte & !0b11 != 0 {
+            return decoder.error(ber::ErrorKind::InvalidValue { tag: None });
+        }
+        Ok(Self {
+            read: byte & 0b01 != 0,
+            write: byte & 0b10 != 0,
+        })
+    }
+}
+
+impl Encodable for Flags {
+    fn encoded_length(&self) -> ber::Result<ber::Length> {
+        Ok(1u8.into())
+    }
+
+    fn encode(&self, encoder: &mut ber::Encoder<'_>) -> ber::Result<()> {
+        encoder.encode(&[(self.read as u8) | ((self.write as u8) << 1)])
+    }
+}
+
+#[derive(Clone, Copy, Debug, Decodable, Encodable, Eq, PartialEq)]
+#[tlv(constructed, number = "0x10")]
+struct Permissions {
+    #[tlv(slice, context, number = "0x0")]
+    id: [u8; 1],
+    #[tlv(context, number = "0x1")]
+    flags: Flags,
+}
+
+#[test]
+fn derive_custom_decodable_field() {
+    let permissions = Permissions {
+        id: [7],
+        flags: Flags {
+            read: true,
+            write: false,
+        },
+    };
+
+    let mut buf = [0u8; 16];
+    let encoded = permissions.encode_to_slice(&mut buf).unwrap();
+    assert_eq!(encoded, &[0x30, 6, 0x80, 1, 7, 0x81, 1, 0b01]);
+    assert_eq!(Permissions::from_bytes(encoded).unwrap(), permissions);
+
+    // the content must be consumed entirely by the field's decoder
+    let err = Permissions::from_bytes(&[0x30, 7, 0x80, 1, 7, 0x81, 2, 0b01, 0]).unwrap_err();
+    assert_eq!(
+        err.kind(),
+        ber::ErrorKind::TrailingData {
+            decoded: 1u8.into(),
+            remaining: 1u8.into(),
+        }
+    );
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub struct PinUsagePolicy {
     piv_pin: bool,
     global_pin: bool,
@@ -175,7 +929,7 @@ impl Default for PinUsagePolicy {
 
 impl Decodable<'_> for PinUsagePolicy {
     fn decode(decoder: &mut ber::Decoder<'_>) -> ber::Result<Self> {
-        let raw: [u8; 2] = decoder.decode()?;
+        let raw = decoder.read_bytes(2)?;
         let capabilities = raw[0];
         let has_global_pin = capabilities & (1 << 5) != 0;
         let has_virtual_contact_interface = capabilities & (1 << 3) != 0;
@@ -238,7 +992,7 @@ impl Encodable for PinUsagePolicy {
     }
 }
 
-#[derive(Decodable, Encodable)]
+#[derive(Clone, Copy, Debug, Decodable, Encodable, Eq, PartialEq)]
 #[tlv(application, constructed, number = "0x1E")] // = 0x7E
 pub struct DiscoveryObject {
     #[tlv(slice, application, number = "0xF")]
@@ -265,4 +1019,167 @@ fn discovery() {
         encoded,
         hex_literal::hex!("7e124f0ba0000003080000100001005f2f024000")
     );
+    assert_eq!(DiscoveryObject::from_bytes(encoded).unwrap(), disco);
+
+    // the policy decoder only sees the content of `5F2F`
+    let disco = DiscoveryObject {
+        pin_usage_policy: PinUsagePolicy {
+            global_pin: true,
+            cardholder_prefers_global_pin: Some(true),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let encoded = disco.encode_to_slice(&mut buf).unwrap();
+    assert_eq!(
+        encoded,
+        hex_literal::hex!("7e124f0ba0000003080000100001005f2f026020")
+    );
+    assert_eq!(DiscoveryObject::from_bytes(encoded).unwrap(), disco);
+}
+
+mod tags {
+    use flexiber::Tag;
+
+    pub const DISCOVERY: Tag = Tag::application(0x1E).constructed();
+    pub const AID: Tag = Tag::application(0xF);
+    pub const PIN_USAGE_POLICY: Tag = Tag::application(0x2F);
+}
+
+#[derive(Clone, Copy, Debug, Decodable, Encodable, Eq, PartialEq)]
+#[tlv(tag = "tags::DISCOVERY")]
+pub struct DiscoveryByPath {
+    #[tlv(slice, tag = "tags::AID")]
+    piv_card_application_aid: [u8; 11],
+    #[tlv(tag = "crate::tags::PIN_USAGE_POLICY")]
+    pin_usage_policy: PinUsagePolicy,
+}
+
+#[test]
+fn tag_paths() {
+    let disco = DiscoveryObject::default();
+    let by_path = DiscoveryByPath {
+        piv_card_application_aid: disco.piv_card_application_aid,
+        pin_usage_policy: disco.pin_usage_policy,
+    };
+
+    let mut buf = [0u8; 64];
+    let mut other_buf = [0u8; 64];
+    let encoded = by_path.encode_to_slice(&mut buf).unwrap();
+    assert_eq!(encoded, disco.encode_to_slice(&mut other_buf).unwrap());
+    assert_eq!(DiscoveryByPath::from_bytes(encoded).unwrap(), by_path);
+}
+
+#[derive(Clone, Copy, Debug, Decodable, Encodable, Eq, PartialEq)]
+#[tlv(simple = "0xfe")]
+struct HighestSimpleTag {
+    #[tlv(simple = "1")]
+    first: [u8; 1],
+}
+
+#[derive(Clone, Copy, Debug, Decodable, Encodable, Eq, PartialEq)]
+#[tlv(private, number = "3fff")]
+struct HighBerTag {
+    #[tlv(context, number = "0x0")]
+    first: [u8; 1],
+}
+
+#[test]
+fn tag_number_bounds() {
+    let mut buf = [0u8; 16];
+
+    let simple = HighestSimpleTag { first: [0xAA] };
+    let encoded = simple.encode_to_slice(&mut buf).unwrap();
+    assert_eq!(encoded, &[0xFE, 3, 0x01, 1, 0xAA]);
+    assert_eq!(HighestSimpleTag::from_bytes(encoded).unwrap(), simple);
+
+    let ber = HighBerTag { first: [0xAA] };
+    let encoded = ber.encode_to_slice(&mut buf).unwrap();
+    assert_eq!(encoded, &[0xFF, 0xFF, 0x7F, 3, 0x80, 1, 0xAA]);
+    assert_eq!(HighBerTag::from_bytes(encoded).unwrap(), ber);
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Decodable, Encodable)]
+#[tlv(number = "0x12")]
+struct OptionalArrays {
+    #[tlv(optional, slice, context, number = "0x1")]
+    id: Option<[u8; 2]>,
+    #[tlv(slice, context, number = "0x2")]
+    key: [u8; 3],
+}
+
+#[test]
+fn derive_optional_slice() {
+    let mut buf = [0u8; 16];
+
+    let present = OptionalArrays {
+        id: Some([1, 2]),
+        key: [3, 4, 5],
+    };
+    let encoded = present.encode_to_slice(&mut buf).unwrap();
+    assert_eq!(encoded, &[0x32, 9, 0x81, 2, 1, 2, 0x82, 3, 3, 4, 5]);
+    assert_eq!(OptionalArrays::from_bytes(encoded).unwrap(), present);
+
+    let absent = OptionalArrays {
+        id: None,
+        key: [3, 4, 5],
+    };
+    let encoded = absent.encode_to_slice(&mut buf).unwrap();
+    assert_eq!(encoded, &[0x32, 5, 0x82, 3, 3, 4, 5]);
+    assert_eq!(OptionalArrays::from_bytes(encoded).unwrap(), absent);
+
+    // a present value is still checked against the array length
+    let err = OptionalArrays::from_bytes(&[0x32, 8, 0x81, 1, 1, 0x82, 3, 3, 4, 5]).unwrap_err();
+    assert_eq!(
+        err.kind(),
+        ber::ErrorKind::Length {
+            tag: ber::Tag::context(0x1),
+            expected: Some(2u8.into()),
+            actual: 1u8.into(),
+        }
+    );
+}
+
+#[derive(Debug, Decodable, Encodable, Eq, PartialEq)]
+#[tlv(number = "0x13")]
+struct Marked<'a> {
+    #[tlv(context, number = "0x1")]
+    value: u8,
+    #[tlv(skip)]
+    _marker: core::marker::PhantomData<&'a ()>,
+}
+
+#[test]
+fn derive_skipped_marker() {
+    let marked = Marked {
+        value: 0x42,
+        _marker: core::marker::PhantomData,
+    };
+    let mut buf = [0u8; 8];
+    let encoded = marked.encode_to_slice(&mut buf).unwrap();
+    assert_eq!(encoded, &[0x33, 3, 0x81, 1, 0x42]);
+    assert_eq!(Marked::from_bytes(encoded).unwrap(), marked);
+}
+
+#[derive(Debug, Decodable, Encodable, Eq, PartialEq)]
+#[tlv(context, number = "0x0")]
+struct Validity {
+    #[tlv(context, number = "0x1")]
+    range: core::ops::Range<u32>,
+}
+
+#[test]
+fn derive_strict_nested() {
+    // the start of the range has a redundant leading zero
+    let encoded = &[0xA0, 12, 0x81, 10, 0x30, 8, 0x02, 2, 0, 1, 0x02, 2, 0, 0x80];
+    assert_eq!(
+        Validity::from_bytes(encoded).unwrap(),
+        Validity { range: 1..0x80 }
+    );
+
+    let err = ber::Decoder::new(encoded)
+        .with_strict()
+        .decode::<Validity>()
+        .unwrap_err();
+    assert_eq!(err.kind(), ber::ErrorKind::NonCanonicalInteger);
 }
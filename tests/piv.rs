@@ -0,0 +1,170 @@
+//! Conformance vectors for PIV data objects, as specified in NIST SP 800-73-4
+//!
+//! PIV data objects use BER-TLV lengths throughout, also for the one-byte tags
+//! inside the CHUID and the other containers wrapped in `0x53`. These are decoded
+//! as [`Tag`]s, since a [`SimpleTag`] implies SIMPLE-TLV lengths.
+
+use flexiber::{
+    Decodable, Decoder, Encodable, Encoder, Length, SimpleTag, Tag, TagLike, TaggedSlice,
+};
+use hex_literal::hex;
+
+/// `0x53`, wrapping the content of a data object in GET DATA and PUT DATA
+const DATA: Tag = Tag::application(0x13);
+
+/// FASC-N of the SP 800-73-4 test cards: agency 9999, system 9999, credential 999999
+const FASC_N: [u8; 25] = hex!("D4E739DA739CED39CE739D836858210842108421C84210C3EB");
+
+/// Decode the data objects of `content` one after the other, re-encode each of them
+/// and check the output is byte-for-byte identical.
+fn reencode<'a, T>(content: &'a [u8]) -> usize
+where
+    T: Decodable<'a> + Encodable + TagLike,
+{
+    let mut buf = [0u8; 1024];
+    let mut decoder = Decoder::new(content);
+    let mut encoder = Encoder::new(&mut buf);
+    let mut count = 0;
+    while !decoder.is_finished() {
+        let object: TaggedSlice<'a, T> = decoder.decode().unwrap();
+        encoder.encode(&object).unwrap();
+        count += 1;
+    }
+    assert_eq!(encoder.finish().unwrap(), content);
+    count
+}
+
+#[test]
+fn chuid() {
+    let encoded = hex!(
+        "53 3B"
+        "30 19 D4E739DA739CED39CE739D836858210842108421C84210C3EB"
+        "34 10 00112233445566778899AABBCCDDEEFF"
+        "35 08 3230333031323331"
+        "3E 00"
+        "FE 00"
+    );
+
+    let content = TaggedSlice::<Tag>::from_bytes(&encoded).unwrap();
+    assert_eq!(content.tag(), DATA);
+    assert_eq!(reencode::<Tag>(content.as_bytes()), 5);
+
+    let mut decoder = Decoder::new(content.as_bytes());
+    let fasc_n = decoder.decode_tagged_slice(Tag::try_from(0x30).unwrap());
+    assert_eq!(fasc_n.unwrap(), FASC_N);
+    let guid = decoder.decode_tagged_slice(Tag::try_from(0x34).unwrap());
+    assert_eq!(guid.unwrap(), hex!("00112233445566778899AABBCCDDEEFF"));
+    let expiration = decoder.decode_tagged_slice(Tag::try_from(0x35).unwrap());
+    assert_eq!(expiration.unwrap(), b"20301231");
+
+    let mut buf = [0u8; 64];
+    assert_eq!(content.encode_to_slice(&mut buf).unwrap(), encoded);
+}
+
+#[test]
+fn chuid_with_signature() {
+    // an issuer asymmetric signature of 0x100 bytes needs the three-byte BER-TLV length,
+    // which also applies to the enclosing `0x53`
+    let mut encoded = [0u8; 4 + 27 + 4 + 0x100 + 2];
+    encoded[..4].copy_from_slice(&hex!("53 82 01 21"));
+    encoded[4..6].copy_from_slice(&hex!("30 19"));
+    encoded[6..31].copy_from_slice(&FASC_N);
+    encoded[31..35].copy_from_slice(&hex!("3E 82 01 00"));
+    encoded[35..291].fill(0xA5);
+    encoded[291..].copy_from_slice(&hex!("FE 00"));
+
+    let content = TaggedSlice::<Tag>::from_bytes(&encoded).unwrap();
+    assert_eq!(content.length(), Length::from(0x121u16));
+    assert_eq!(reencode::<Tag>(content.as_bytes()), 3);
+
+    let mut buf = [0u8; 512];
+    assert_eq!(content.encode_to_slice(&mut buf).unwrap(), encoded);
+
+    // with a simple tag, the same signature would have a SIMPLE-TLV length
+    let signature = TaggedSlice::from(SimpleTag::try_from(0x3E).unwrap(), &encoded[35..291]);
+    let reencoded = signature.unwrap().encode_to_slice(&mut buf).unwrap();
+    assert_eq!(reencoded[..4], hex!("3E FF 01 00"));
+}
+
+#[test]
+fn discovery_object() {
+    let encoded = hex!("7E 12 4F 0B A0000003080000100001 00 5F2F 02 4000");
+
+    let object = TaggedSlice::<Tag>::from_bytes(&encoded).unwrap();
+    assert_eq!(object.tag(), Tag::application(0x1E).constructed());
+    assert_eq!(reencode::<Tag>(object.as_bytes()), 2);
+
+    let mut decoder = Decoder::new(object.as_bytes());
+    let aid = decoder.decode_tagged_slice(Tag::application(0xF)).unwrap();
+    assert_eq!(aid, hex!("A000000308 00001000 0100"));
+    let policy = decoder.decode_tagged_slice(Tag::application(0x2F)).unwrap();
+    assert_eq!(policy, hex!("4000"));
+}
+
+#[test]
+fn application_property_template() {
+    // response to SELECT of the PIV card application
+    let encoded = hex!("61 11 4F 06 000010000100 79 07 4F 05 A000000308");
+
+    let template = TaggedSlice::<Tag>::from_bytes(&encoded).unwrap();
+    assert_eq!(template.tag(), Tag::application(0x1).constructed());
+    assert_eq!(reencode::<Tag>(template.as_bytes()), 2);
+
+    let mut decoder = Decoder::new(template.as_bytes());
+    let pix = decoder.decode_tagged_slice(Tag::application(0xF)).unwrap();
+    assert_eq!(pix, hex!("000010000100"));
+    let authority = decoder
+        .decode_tagged_slice(Tag::application(0x19).constructed())
+        .unwrap();
+    assert_eq!(reencode::<Tag>(authority), 1);
+}
+
+#[test]
+fn key_history_object() {
+    let encoded = hex!("53 08 C1 01 02 C2 01 00 FE 00");
+
+    let content = TaggedSlice::<Tag>::from_bytes(&encoded).unwrap();
+    assert_eq!(content.tag(), DATA);
+    assert_eq!(reencode::<Tag>(content.as_bytes()), 3);
+
+    let mut decoder = Decoder::new(content.as_bytes());
+    let on_card: u8 = decoder.decode_tagged_value(Tag::private(0x1)).unwrap();
+    let off_card: u8 = decoder.decode_tagged_value(Tag::private(0x2)).unwrap();
+    assert_eq!((on_card, off_card), (2, 0));
+    let lrc = decoder.decode_tagged_slice(Tag::private(0x1E).constructed());
+    assert!(lrc.unwrap().is_empty());
+}
+
+#[test]
+fn get_data() {
+    // the data field of GET DATA for the CHUID, whose three-byte tag is the content of `0x5C`
+    let encoded = hex!("5C 03 5FC102");
+
+    let tag_list = TaggedSlice::<Tag>::from_bytes(&encoded).unwrap();
+    assert_eq!(tag_list.tag(), Tag::application(0x1C));
+    assert_eq!(
+        Tag::from_bytes(tag_list.as_bytes()).unwrap(),
+        Tag::application(0x2082)
+    );
+
+    let mut buf = [0u8; 8];
+    let chuid = Tag::application(0x2082);
+    let tag_list = Tag::application(0x1C).with_value(&chuid);
+    assert_eq!(tag_list.encode_to_slice(&mut buf).unwrap(), encoded);
+}
+
+#[test]
+fn dynamic_authentication_template() {
+    // GENERAL AUTHENTICATE requesting a response to an 8 byte challenge
+    let encoded = hex!("7C 0C 82 00 81 08 0102030405060708");
+
+    let template = TaggedSlice::<Tag>::from_bytes(&encoded).unwrap();
+    assert_eq!(template.tag(), Tag::application(0x1C).constructed());
+    assert_eq!(reencode::<Tag>(template.as_bytes()), 2);
+
+    let mut decoder = Decoder::new(template.as_bytes());
+    let response = decoder.decode_tagged_slice(Tag::context(0x2)).unwrap();
+    assert!(response.is_empty());
+    let challenge = decoder.decode_tagged_slice(Tag::context(0x1)).unwrap();
+    assert_eq!(challenge, hex!("0102030405060708"));
+}
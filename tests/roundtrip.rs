@@ -0,0 +1,61 @@
+//! Property-based round-trip tests for encoding and decoding
+
+use core::convert::TryFrom;
+use flexiber::{Class, Decodable, Encodable, Length, SimpleTag, Tag, TaggedSlice};
+use proptest::prelude::*;
+
+fn class() -> impl Strategy<Value = Class> {
+    proptest::sample::select(Class::all().to_vec())
+}
+
+/// Tags with numbers in each of the supported one, two and three octet ranges
+fn tag() -> impl Strategy<Value = Tag> {
+    let number = prop_oneof![0u16..=0x1E, 0x1Fu16..=0x7F, 0x80u16..=0x3FFF];
+    (class(), any::<bool>(), number)
+        .prop_map(|(class, constructed, number)| Tag::from(class, constructed, number))
+}
+
+/// Content lengths in each of the one, two and three octet length ranges
+fn content() -> impl Strategy<Value = Vec<u8>> {
+    prop_oneof![
+        proptest::collection::vec(any::<u8>(), 0..=0x7F),
+        proptest::collection::vec(any::<u8>(), 0x80..=0xFF),
+        proptest::collection::vec(any::<u8>(), 0x100..=0x400),
+    ]
+}
+
+proptest! {
+    #[test]
+    fn tag_roundtrip(tag in tag()) {
+        let mut buf = [0u8; 3];
+        let encoded = tag.encode_to_slice(&mut buf).unwrap();
+        prop_assert_eq!(encoded.len(), tag.encoded_length().unwrap().to_usize());
+        prop_assert_eq!(Tag::from_bytes(encoded).unwrap(), tag);
+    }
+
+    #[test]
+    fn length_roundtrip(length in any::<u16>()) {
+        let length = Length::from(length);
+        let mut buf = [0u8; 3];
+        let encoded = length.encode_to_slice(&mut buf).unwrap();
+        prop_assert_eq!(encoded.len(), length.encoded_length().unwrap().to_usize());
+        prop_assert_eq!(Length::from_bytes(encoded).unwrap(), length);
+    }
+
+    #[test]
+    fn tagged_slice_roundtrip(tag in tag(), content in content()) {
+        let tagged = TaggedSlice::from(tag, &content).unwrap();
+        let encoded = tagged.to_vec().unwrap();
+        prop_assert_eq!(encoded.len(), tagged.encoded_length().unwrap().to_usize());
+        prop_assert_eq!(TaggedSlice::<Tag>::from_bytes(&encoded).unwrap(), tagged);
+    }
+
+    #[test]
+    fn simple_tagged_slice_roundtrip(number in 0x01u8..=0xFE, content in content()) {
+        let tag = SimpleTag::try_from(number).unwrap();
+        let tagged = TaggedSlice::from(tag, &content).unwrap();
+        let encoded = tagged.to_vec().unwrap();
+        prop_assert_eq!(encoded.len(), tagged.encoded_length().unwrap().to_usize());
+        prop_assert!(TaggedSlice::<SimpleTag>::from_bytes(&encoded).unwrap() == tagged);
+    }
+}